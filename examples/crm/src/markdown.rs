@@ -3,7 +3,7 @@
 /// Source: https://github.com/acmumn/mentoring/blob/master/web-client/src/view/markdown.rs
 
 use pulldown_cmark::{Alignment, Event, Parser, Tag, OPTION_ENABLE_TABLES};
-use yew::{html, Component, Html};
+use yew::{html, Component, Href, Html};
 use yew::virtual_dom::{VNode, VTag, VText};
 
 /// Renders a string of Markdown to HTML with the default options (footnotes
@@ -144,7 +144,7 @@ where
         Tag::Code => VTag::new("code"),
         Tag::Link(ref href, ref title) => {
             let mut el = VTag::new("a");
-            el.add_attribute("href", href);
+            el.add_attribute("href", &Href::from(href.as_ref()));
             if title != "" {
                 el.add_attribute("title", title);
             }
@@ -152,7 +152,7 @@ where
         }
         Tag::Image(ref src, ref title) => {
             let mut el = VTag::new("img");
-            el.add_attribute("src", src);
+            el.add_attribute("src", &Href::from(src.as_ref()).to_source_string());
             if title != "" {
                 el.add_attribute("title", title);
             }
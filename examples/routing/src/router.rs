@@ -4,7 +4,6 @@ use crate::routing::RouteService;
 use yew::worker::*;
 use log::info;
 use serde_derive::{Deserialize, Serialize};
-use std::collections::HashSet;
 use stdweb::Value;
 use stdweb::JsSerialize;
 use stdweb::unstable::TryFrom;
@@ -93,15 +92,13 @@ impl <T> Transferable for Request <T>
 {}
 
 /// The Router worker holds on to the RouteService singleton and mediates access to it.
+/// When a route changes, either initiated by the browser or by the app,
+/// the route change is broadcast to all connected entities.
 pub struct Router<T>
     where for <'de> T: JsSerialize + Clone + Debug + TryFrom<Value> + Default + Serialize + Deserialize<'de> + 'static
 {
     link: AgentLink<Router<T>>,
     route_service: RouteService<T>,
-    /// A list of all entities connected to the router.
-    /// When a route changes, either initiated by the browser or by the app,
-    /// the route change will be broadcast to all listening entities.
-    subscribers: HashSet<HandlerId>,
 }
 
 impl<T> Agent for Router<T>
@@ -120,7 +117,6 @@ impl<T> Agent for Router<T>
         Router {
             link,
             route_service,
-            subscribers: HashSet::new(),
         }
     }
 
@@ -130,9 +126,7 @@ impl<T> Agent for Router<T>
                 info!("Browser navigated");
                 let mut route = Route::current_route(&self.route_service);
                 route.state = state;
-                for sub in self.subscribers.iter() {
-                    self.link.response(*sub, route.clone());
-                }
+                self.link.broadcast(route);
             }
         }
     }
@@ -147,9 +141,7 @@ impl<T> Agent for Router<T>
                 // get the new route. This will contain a default state object
                 let route = Route::current_route(&self.route_service);
                 // broadcast it to all listening components
-                for sub in self.subscribers.iter() {
-                    self.link.response(*sub, route.clone());
-                }
+                self.link.broadcast(route);
             }
             Request::ChangeRouteNoBroadcast(route) => {
                 let route_string: String = route.to_route_string();
@@ -161,11 +153,4 @@ impl<T> Agent for Router<T>
             }
         }
     }
-
-    fn connected(&mut self, id: HandlerId) {
-        self.subscribers.insert(id);
-    }
-    fn disconnected(&mut self, id: HandlerId) {
-        self.subscribers.remove(&id);
-    }
 }
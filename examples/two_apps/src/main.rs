@@ -1,9 +1,8 @@
 use stdweb::web::{IParentNode, document};
-use yew::App;
-use yew::html::Scope;
+use yew::{App, AppHandle};
 use two_apps::{Model, Msg};
 
-fn mount_app(selector: &'static str, app: App<Model>) -> Scope<Model> {
+fn mount_app(selector: &'static str, app: App<Model>) -> AppHandle<Model> {
     let element = document().query_selector(selector).unwrap().unwrap();
     app.mount(element)
 }
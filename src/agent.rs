@@ -1,20 +1,37 @@
 //! This module contains types to support multi-threading in Yew.
 
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+use std::cell::Cell;
+use std::time::Duration;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::panic;
 use std::marker::PhantomData;
-use serde::{Serialize, Deserialize};
+use failure::Error;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error as DeError;
 use bincode;
 use anymap::{AnyMap, Entry};
 use slab::Slab;
 use stdweb::Value;
+use stdweb::unstable::TryInto;
+use stdweb::web::{ArrayBuffer, TypedArray};
 use scheduler::{Runnable, Shared, scheduler};
 use callback::Callback;
+use services::Task;
+use services::timeout::{TimeoutService, TimeoutTask};
+use format::{Binary, Format, Json, Text};
+#[cfg(feature = "msgpack")]
+use format::MsgPack;
+#[cfg(feature = "cbor")]
+use format::Cbor;
 
 #[derive(Serialize, Deserialize)]
 enum ToWorker<T> {
     Connected(HandlerId),
     ProcessInput(HandlerId, T),
+    ProcessRequest(RequestId, T),
     Disconnected(HandlerId),
     Destroy,
 }
@@ -28,10 +45,26 @@ where
 #[derive(Serialize, Deserialize)]
 enum FromWorker<T> {
     /// Worker sends this message when `wasm` bundle has loaded.
-    WorkerLoaded,
+    WorkerLoaded {
+        /// Version of the envelope, see `ENVELOPE_VERSION`.
+        envelope: u32,
+        /// Version of messages of the agent, see `Agent::protocol_version`.
+        protocol: u32,
+    },
     ProcessOutput(HandlerId, T),
+    ProcessResponse(RequestId, T),
+    /// Worker sends this message when an input was handled by the agent.
+    InputProcessed(HandlerId),
+    /// Worker sends this message when it can't decode an incoming message.
+    DecodeFailed(String),
+    /// Shared worker sends this message to every tab when the agent has panicked,
+    /// because errors of a shared worker don't reach its owners.
+    WorkerFailed(String),
 }
 
+/// Version of `ToWorker` and `FromWorker` envelopes. Bump it on every change of them.
+const ENVELOPE_VERSION: u32 = 4;
+
 impl<T> Transferable for FromWorker<T>
 where
     T: Serialize + for <'de> Deserialize<'de>,
@@ -46,37 +79,197 @@ where
 {
 }
 
-trait Packed {
-    fn pack(&self) -> Vec<u8>;
-    fn unpack(data: &Vec<u8>) -> Self;
+/// A wire format of messages between bridges and a worker of an agent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+    /// A compact binary format. It's used by default.
+    Bincode,
+    /// A JSON text which is easy to read in a debugger.
+    Json,
+    /// A CBOR binary format.
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// A MessagePack binary format.
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+}
+
+impl Codec {
+    fn encode<T: Serialize>(self, value: &T) -> Binary {
+        match self {
+            Codec::Bincode => bincode::serialize(value).map_err(Error::from),
+            Codec::Json => {
+                let text: Text = Json(value).into();
+                text.map(String::into_bytes)
+            },
+            #[cfg(feature = "cbor")]
+            Codec::Cbor => Cbor(value).into(),
+            #[cfg(feature = "msgpack")]
+            Codec::MsgPack => MsgPack(value).into(),
+        }
+    }
+
+    fn decode<T>(self, data: Vec<u8>) -> Format<T>
+    where
+        T: for <'de> Deserialize<'de>,
+    {
+        match self {
+            Codec::Bincode => bincode::deserialize(&data).map_err(Error::from),
+            Codec::Json => {
+                let text: Text = String::from_utf8(data).map_err(Error::from);
+                Json::from(text).into()
+            },
+            #[cfg(feature = "cbor")]
+            Codec::Cbor => Cbor::from(Ok(data)).into(),
+            #[cfg(feature = "msgpack")]
+            Codec::MsgPack => MsgPack::from(Ok(data)).into(),
+        }
+    }
+}
+
+/// A serialized message with buffers which are moved with it.
+#[derive(Clone)]
+struct Packet {
+    data: Vec<u8>,
+    buffers: Vec<ArrayBuffer>,
+}
+
+impl Packet {
+    /// Collects a packet from fields of a received message.
+    fn receive(data: Vec<u8>, buffers: Value) -> Self {
+        let buffers = buffers.try_into().unwrap_or_default();
+        Packet { data, buffers }
+    }
+}
+
+/// Buffers of a message which is serialized or deserialized now.
+/// `TransferBuffer`s refer to them by indices.
+#[derive(Default)]
+struct TransferLists {
+    outgoing: Vec<ArrayBuffer>,
+    incoming: Vec<Option<ArrayBuffer>>,
+}
+
+thread_local! {
+    static TRANSFER_LISTS: RefCell<TransferLists> = RefCell::new(TransferLists::default());
+}
+
+trait Packed: Sized {
+    fn pack(&self, codec: Codec) -> Packet;
+    fn unpack(packet: Packet, codec: Codec) -> Format<Self>;
 }
 
 impl<T: Transferable> Packed for T {
-    fn pack(&self) -> Vec<u8> {
-        bincode::serialize(&self)
-            .expect("can't serialize a transferable object")
+    fn pack(&self, codec: Codec) -> Packet {
+        TRANSFER_LISTS.with(|lists| lists.borrow_mut().outgoing.clear());
+        let data = codec.encode(self)
+            .expect("can't serialize a transferable object");
+        let buffers = TRANSFER_LISTS.with(|lists| mem::take(&mut lists.borrow_mut().outgoing));
+        Packet { data, buffers }
+    }
+
+    fn unpack(packet: Packet, codec: Codec) -> Format<Self> {
+        let Packet { data, buffers } = packet;
+        TRANSFER_LISTS.with(|lists| {
+            lists.borrow_mut().incoming = buffers.into_iter().map(Some).collect();
+        });
+        let result = codec.decode(data);
+        TRANSFER_LISTS.with(|lists| lists.borrow_mut().incoming.clear());
+        result
+    }
+}
+
+/// A binary buffer which is moved to or from a worker in the transfer list
+/// of `postMessage`. It's not serialized and not copied, but the buffer
+/// becomes unusable on the sending side. A message can't contain the same buffer twice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferBuffer(ArrayBuffer);
+
+impl TransferBuffer {
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len() as usize
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the buffer.
+    pub fn buffer(&self) -> &ArrayBuffer {
+        &self.0
+    }
+
+    /// Copies bytes of the buffer to a vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        Vec::from(&self.0)
+    }
+}
+
+impl From<ArrayBuffer> for TransferBuffer {
+    fn from(buffer: ArrayBuffer) -> Self {
+        TransferBuffer(buffer)
+    }
+}
+
+impl From<TransferBuffer> for ArrayBuffer {
+    fn from(buffer: TransferBuffer) -> Self {
+        buffer.0
+    }
+}
+
+impl<'a> From<&'a [u8]> for TransferBuffer {
+    fn from(data: &'a [u8]) -> Self {
+        TransferBuffer(TypedArray::<u8>::from(data).buffer())
+    }
+}
+
+impl Serialize for TransferBuffer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Only an index of the buffer in the transfer list is serialized
+        let index = TRANSFER_LISTS.with(|lists| {
+            let outgoing = &mut lists.borrow_mut().outgoing;
+            outgoing.push(self.0.clone());
+            outgoing.len() - 1
+        });
+        serializer.serialize_u32(index as u32)
     }
+}
 
-    fn unpack(data: &Vec<u8>) -> Self {
-        bincode::deserialize(&data)
-            .expect("can't deserialize a transferable object")
+impl<'de> Deserialize<'de> for TransferBuffer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)? as usize;
+        TRANSFER_LISTS
+            .with(|lists| lists.borrow_mut().incoming.get_mut(index).and_then(Option::take))
+            .map(TransferBuffer)
+            .ok_or_else(|| D::Error::custom("transferred buffer is missing"))
     }
 }
 
 /// Id of responses handler.
-#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
-pub struct HandlerId(usize);
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct HandlerId(usize, bool);
 
 impl From<usize> for HandlerId {
     fn from(id: usize) -> Self {
-        HandlerId(id)
+        HandlerId(id, true)
     }
 }
 
 impl HandlerId {
+    fn new(id: usize, respondable: bool) -> Self {
+        HandlerId(id, respondable)
+    }
+
     fn raw_id(&self) -> usize {
         self.0
     }
+
+    /// Returns `true` if the handler gets outputs. It's `false` for a `Dispatcher`.
+    pub fn is_respondable(&self) -> bool {
+        self.1
+    }
 }
 
 /// This traits allow to get addres or register worker.
@@ -85,6 +278,32 @@ pub trait Bridged: Agent + Sized + 'static {
     fn bridge(callback: Callback<Self::Output>) -> Box<dyn Bridge<Self>>;
 }
 
+/// This trait allows to send messages to an agent without getting outputs.
+pub trait Dispatched: Agent + Sized + 'static {
+    /// Creates a dispatcher which sends inputs to the agent.
+    fn dispatcher() -> Dispatcher<Self>;
+}
+
+impl<T> Dispatched for T
+where
+    T: Agent,
+{
+    fn dispatcher() -> Dispatcher<Self> {
+        Dispatcher(Self::Reach::spawn_or_join(None))
+    }
+}
+
+/// A send-only connection to an agent. The agent sees it as a connected handler
+/// which is not respondable and it doesn't get broadcasts of the agent.
+pub struct Dispatcher<T: Agent>(Box<dyn Bridge<T>>);
+
+impl<T: Agent> Dispatcher<T> {
+    /// Sends a message to the agent.
+    pub fn send(&mut self, msg: T::Input) {
+        self.0.send(msg);
+    }
+}
+
 /// Implements rules to register a worker in a separate thread.
 pub trait Threaded {
     /// Executes an agent in the current environment.
@@ -94,49 +313,84 @@ pub trait Threaded {
 
 impl<T> Threaded for T
 where
-    T: Agent<Reach=Public>,
+    T: Agent,
+    T::Reach: Remote,
 {
     fn register() {
-        let scope = AgentScope::<T>::new();
-        let responder = WorkerResponder { };
-        let link = AgentLink::connect(&scope, responder);
-        let upd = AgentUpdate::Create(link);
-        scope.send(upd);
-        let handler = move |data: Vec<u8>| {
-            let msg = ToWorker::<T::Input>::unpack(&data);
-            match msg {
-                ToWorker::Connected(id) => {
-                    let upd = AgentUpdate::Connected(id);
-                    scope.send(upd);
-                },
-                ToWorker::ProcessInput(id, value) => {
-                    let upd = AgentUpdate::Input(value, id);
-                    scope.send(upd);
-                },
-                ToWorker::Disconnected(id) => {
-                    let upd = AgentUpdate::Disconnected(id);
-                    scope.send(upd);
-                },
-                ToWorker::Destroy => {
-                    let upd = AgentUpdate::Destroy;
-                    scope.send(upd);
-                    js! {
-                        // Terminates web worker
-                        self.close();
-                    };
-                },
-            }
+        T::Reach::register::<T>();
+    }
+}
+
+/// Checks that a worker reports processed inputs with `InputProcessed`. Bridges count
+/// unprocessed inputs only to balance a pool and to report inputs lost by a crash.
+fn counts_inputs<AGN: Agent>(balanced: bool) -> bool {
+    balanced || AGN::restart_policy() != RestartPolicy::Never
+}
+
+/// Runs an agent in a dedicated worker.
+fn register_dedicated<T: Agent>(counted: bool) {
+    let scope = AgentScope::<T>::new();
+    let responder = WorkerResponder { };
+    let link = AgentLink::connect(&scope, responder);
+    let upd = AgentUpdate::Create(link);
+    scope.send(upd);
+    let handler = move |data: Vec<u8>, buffers: Value| {
+        let packet = Packet::receive(data, buffers);
+        let msg = match ToWorker::<T::Input>::unpack(packet, T::codec()) {
+            Ok(msg) => msg,
+            Err(err) => {
+                warn!("Can't decode a message to the agent: {}", err);
+                let failed = FromWorker::<T::Output>::DecodeFailed(err.to_string());
+                post_to_parent(failed.pack(T::codec()));
+                return;
+            },
         };
-        let loaded: FromWorker<T::Output> = FromWorker::WorkerLoaded;
-        let loaded = loaded.pack();
-        js! {
-            var handler = @{handler};
-            self.onmessage = function(event) {
-                handler(event.data);
-            };
-            self.postMessage(@{loaded});
+        match msg {
+            ToWorker::Connected(id) => {
+                let upd = AgentUpdate::Connected(id);
+                scope.send(upd);
+            },
+            ToWorker::ProcessInput(id, value) => {
+                let upd = AgentUpdate::Input(value, id);
+                scope.send(upd);
+                if counted {
+                    let processed = FromWorker::<T::Output>::InputProcessed(id);
+                    post_to_parent(processed.pack(T::codec()));
+                }
+            },
+            ToWorker::ProcessRequest(request, value) => {
+                let upd = AgentUpdate::Request(value, request);
+                scope.send(upd);
+                if counted {
+                    let processed = FromWorker::<T::Output>::InputProcessed(request.handler);
+                    post_to_parent(processed.pack(T::codec()));
+                }
+            },
+            ToWorker::Disconnected(id) => {
+                let upd = AgentUpdate::Disconnected(id);
+                scope.send(upd);
+            },
+            ToWorker::Destroy => {
+                let upd = AgentUpdate::Destroy;
+                scope.send(upd);
+                js! {
+                    // Terminates web worker
+                    self.close();
+                };
+            },
+        }
+    };
+    let loaded: FromWorker<T::Output> = FromWorker::WorkerLoaded {
+        envelope: ENVELOPE_VERSION,
+        protocol: T::protocol_version(),
+    };
+    js! {
+        var handler = @{handler};
+        self.onmessage = function(event) {
+            handler(event.data.bytes, event.data.buffers);
         };
-    }
+    };
+    post_to_parent(loaded.pack(T::codec()));
 }
 
 impl<T> Bridged for T
@@ -144,7 +398,7 @@ where
     T: Agent,
 {
     fn bridge(callback: Callback<Self::Output>) -> Box<dyn Bridge<Self>> {
-        Self::Reach::spawn_or_join(callback)
+        Self::Reach::spawn_or_join(Some(callback))
     }
 }
 
@@ -152,22 +406,266 @@ where
 #[doc(hidden)]
 pub trait Discoverer {
     /// Spawns an agent and returns `Bridge` implementation.
-    fn spawn_or_join<AGN: Agent>(_callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
+    fn spawn_or_join<AGN: Agent>(_callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
         unimplemented!();
     }
 }
 
+/// Marks a reach of agents which live in a separate worker.
+#[doc(hidden)]
+pub trait Remote: Discoverer {
+    /// Tells whether bridges balance inputs across workers by their load.
+    fn balances_inputs() -> bool {
+        false
+    }
+
+    /// Runs an agent in the current worker.
+    fn register<AGN: Agent>() {
+        register_dedicated::<AGN>(counts_inputs::<AGN>(Self::balances_inputs()));
+    }
+}
+
 /// Bridge to a specific kind of worker.
 pub trait Bridge<AGN: Agent> {
     /// Send a message to an agent.
     fn send(&mut self, msg: AGN::Input);
+
+    /// Sends a request to an agent. The callback gets an output which the agent
+    /// responds to the request with or an error if the timeout has elapsed.
+    /// The first output to the bridge during `handle_request` (including a broadcast)
+    /// responds to the request, so it isn't passed to the callback of the bridge.
+    /// Bridges which don't implement it fail requests with `RequestError::Unsupported`.
+    fn request(
+        &mut self,
+        _msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        _timeout: Option<Duration>,
+    ) -> RequestTask {
+        callback.emit(Err(RequestError::Unsupported));
+        RequestTask::finished()
+    }
+
+    /// Sets a callback which is notified when a worker of the agent has crashed.
+    /// Agents which live in the current thread never report errors.
+    fn set_error_handler(&mut self, _callback: Callback<AgentError>) { }
+}
+
+/// Describes what to do when a worker of an agent has crashed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartPolicy {
+    /// Leaves the worker dead. Inputs sent after the crash are dropped.
+    Never,
+    /// Respawns the worker after every crash.
+    Always,
+    /// Respawns the worker up to the given number of times.
+    Limited(u32),
+}
+
+/// A kind of an error which is reported to bridges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentErrorKind {
+    /// The worker has failed.
+    Crashed,
+    /// A message can't be decoded by the other side of a bridge.
+    Decode,
+    /// The worker uses other versions of the envelope or of messages of the agent.
+    Handshake,
+}
+
+/// An error of a worker which is reported to bridges.
+#[derive(Debug, Clone)]
+pub struct AgentError {
+    /// The kind of the error.
+    pub kind: AgentErrorKind,
+    /// The message of the error event.
+    pub message: String,
+    /// `true` if the worker was respawned according to the `RestartPolicy` of the agent.
+    pub restarted: bool,
+    /// Count of inputs of the bridge which were sent but weren't handled by the agent.
+    /// Inputs are counted for `Pool` agents and `Private` or `Public` agents with a
+    /// `RestartPolicy` other than `Never` only, it's always `0` for others.
+    pub lost_inputs: usize,
+}
+
+/// Id of a request which was sent with `Bridge::request`.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct RequestId {
+    handler: HandlerId,
+    serial: u32,
+}
+
+impl RequestId {
+    /// Returns an id of the bridge which has sent the request.
+    pub fn handler(&self) -> HandlerId {
+        self.handler
+    }
+}
+
+/// An error of a request to an agent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestError {
+    /// The agent hasn't responded before the timeout has elapsed.
+    TimedOut,
+    /// The bridge doesn't support requests.
+    Unsupported,
+}
+
+/// A callback which gets a response to a request.
+pub type ResponseCallback<OUT> = Callback<Result<OUT, RequestError>>;
+
+struct PendingRequest<OUT> {
+    callback: ResponseCallback<OUT>,
+    timeout: Option<TimeoutTask>,
+}
+
+/// Requests of a bridge which wait for responses.
+struct Requests<OUT> {
+    next_serial: u32,
+    pending: HashMap<u32, PendingRequest<OUT>>,
+}
+
+impl<OUT: 'static> Requests<OUT> {
+    fn new() -> Shared<Self> {
+        let requests = Requests {
+            next_serial: 0,
+            pending: HashMap::new(),
+        };
+        Rc::new(RefCell::new(requests))
+    }
+
+    /// Adds a request of a bridge and starts its timeout.
+    fn add(
+        this: &Shared<Self>,
+        handler: HandlerId,
+        callback: ResponseCallback<OUT>,
+        timeout: Option<Duration>,
+    ) -> (RequestId, RequestTask) {
+        let serial = {
+            let mut requests = this.borrow_mut();
+            let serial = requests.next_serial;
+            requests.next_serial = serial.wrapping_add(1);
+            serial
+        };
+        let timeout = timeout.map(|duration| {
+            let requests = Rc::downgrade(this);
+            let callback = Callback::from(move |_| {
+                if let Some(requests) = requests.upgrade() {
+                    Requests::time_out(&requests, serial);
+                }
+            });
+            TimeoutService::new().spawn(duration, callback)
+        });
+        let pending = PendingRequest { callback, timeout };
+        this.borrow_mut().pending.insert(serial, pending);
+        let table: Rc<RefCell<dyn RequestTable>> = this.clone();
+        let task = RequestTask {
+            table: Rc::downgrade(&table),
+            serial,
+        };
+        (RequestId { handler, serial }, task)
+    }
+
+    /// Passes an output of the agent to the callback of a request.
+    fn resolve(this: &Shared<Self>, serial: u32, output: OUT) {
+        let pending = this.borrow_mut().pending.remove(&serial);
+        // Responses to canceled requests are ignored
+        if let Some(pending) = pending {
+            pending.callback.emit(Ok(output));
+        }
+    }
+
+    fn time_out(this: &Shared<Self>, serial: u32) {
+        let pending = this.borrow_mut().pending.remove(&serial);
+        if let Some(pending) = pending {
+            // The timeout has fired, a callback of a task which is dropped
+            // while it's being called is released after it returns
+            drop(pending.timeout);
+            pending.callback.emit(Err(RequestError::TimedOut));
+        }
+    }
+
+    /// Cancels all requests of a dropped bridge.
+    fn cancel_all(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// A type-erased table of requests which is used by `RequestTask`.
+trait RequestTable {
+    fn contains(&self, serial: u32) -> bool;
+    fn cancel(&mut self, serial: u32);
+}
+
+impl<OUT> RequestTable for Requests<OUT> {
+    fn contains(&self, serial: u32) -> bool {
+        self.pending.contains_key(&serial)
+    }
+
+    fn cancel(&mut self, serial: u32) {
+        self.pending.remove(&serial);
+    }
+}
+
+/// A handle of a request which waits for a response. The request is canceled
+/// when the handle is dropped and its callback is never called after that.
+#[must_use]
+pub struct RequestTask {
+    table: Weak<RefCell<dyn RequestTable>>,
+    serial: u32,
+}
+
+impl RequestTask {
+    /// Creates a handle of a request which is not active.
+    fn finished() -> Self {
+        let table: Weak<RefCell<Requests<()>>> = Weak::new();
+        RequestTask {
+            table,
+            serial: 0,
+        }
+    }
+}
+
+impl Task for RequestTask {
+    fn is_active(&self) -> bool {
+        match self.table.upgrade() {
+            Some(table) => table.borrow().contains(self.serial),
+            None => false,
+        }
+    }
+
+    fn cancel(&mut self) {
+        if let Some(table) = self.table.upgrade() {
+            table.borrow_mut().cancel(self.serial);
+        }
+    }
+}
+
+impl Drop for RequestTask {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// A bridge which is connected to an agent. A dispatcher has no callback.
+struct Subscriber<OUT> {
+    callback: Option<Callback<OUT>>,
+    requests: Shared<Requests<OUT>>,
+}
+
+impl<OUT> Subscriber<OUT> {
+    fn new(callback: Option<Callback<OUT>>, requests: &Shared<Requests<OUT>>) -> Self {
+        Subscriber {
+            callback,
+            requests: requests.clone(),
+        }
+    }
 }
 
 // <<< SAME THREAD >>>
 
 struct LocalAgent<AGN: Agent> {
     scope: AgentScope<AGN>,
-    slab: Shared<Slab<Callback<AGN::Output>>>,
+    slab: Shared<Slab<Subscriber<AGN::Output>>>,
 }
 
 type Last = bool;
@@ -181,15 +679,18 @@ impl<AGN: Agent> LocalAgent<AGN> {
         }
     }
 
-    fn slab(&self) -> Shared<Slab<Callback<AGN::Output>>> {
+    fn slab(&self) -> Shared<Slab<Subscriber<AGN::Output>>> {
         self.slab.clone()
     }
 
-    fn create_bridge(&mut self, callback: Callback<AGN::Output>) -> ContextBridge<AGN> {
-        let id = self.slab.borrow_mut().insert(callback);
+    fn create_bridge(&mut self, callback: Option<Callback<AGN::Output>>) -> ContextBridge<AGN> {
+        let requests = Requests::new();
+        let respondable = callback.is_some();
+        let id = self.slab.borrow_mut().insert(Subscriber::new(callback, &requests));
         ContextBridge {
             scope: self.scope.clone(),
-            id: id.into(),
+            id: HandlerId::new(id, respondable),
+            requests,
         }
     }
 
@@ -208,7 +709,7 @@ thread_local! {
 pub struct Context;
 
 impl Discoverer for Context {
-    fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
         let mut scope_to_init = None;
         let bridge = LOCAL_AGENTS_POOL.with(|pool| {
             match pool.borrow_mut().entry::<LocalAgent<AGN>>() {
@@ -230,30 +731,41 @@ impl Discoverer for Context {
             let upd = AgentUpdate::Create(agent_link);
             scope.send(upd);
         }
-        let upd = AgentUpdate::Connected(bridge.id.into());
+        let upd = AgentUpdate::Connected(bridge.id);
         bridge.scope.send(upd);
         Box::new(bridge)
     }
 }
 
 struct SlabResponder<AGN: Agent> {
-    slab: Shared<Slab<Callback<AGN::Output>>>,
+    slab: Shared<Slab<Subscriber<AGN::Output>>>,
 }
 
 impl<AGN: Agent> Responder<AGN> for SlabResponder<AGN> {
     fn response(&self, id: HandlerId, output: AGN::Output) {
-        let callback = self.slab.borrow().get(id.raw_id()).cloned();
+        let callback = self.slab.borrow().get(id.raw_id()).and_then(|subscriber| subscriber.callback.clone());
         if let Some(callback) = callback {
             callback.emit(output);
         } else {
             warn!("Id of handler not exists <slab>: {}", id.raw_id());
         }
     }
+
+    fn respond(&self, request: RequestId, output: AGN::Output) {
+        let id = request.handler();
+        let requests = self.slab.borrow().get(id.raw_id()).map(|subscriber| subscriber.requests.clone());
+        if let Some(requests) = requests {
+            Requests::resolve(&requests, request.serial, output);
+        } else {
+            warn!("Id of handler not exists <slab>: {}", id.raw_id());
+        }
+    }
 }
 
 struct ContextBridge<AGN: Agent> {
     scope: AgentScope<AGN>,
     id: HandlerId,
+    requests: Shared<Requests<AGN::Output>>,
 }
 
 impl<AGN: Agent> Bridge<AGN> for ContextBridge<AGN> {
@@ -261,10 +773,23 @@ impl<AGN: Agent> Bridge<AGN> for ContextBridge<AGN> {
         let upd = AgentUpdate::Input(msg, self.id);
         self.scope.send(upd);
     }
+
+    fn request(
+        &mut self,
+        msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        timeout: Option<Duration>,
+    ) -> RequestTask {
+        let (request, task) = Requests::add(&self.requests, self.id, callback, timeout);
+        let upd = AgentUpdate::Request(msg, request);
+        self.scope.send(upd);
+        task
+    }
 }
 
 impl<AGN: Agent> Drop for ContextBridge<AGN> {
     fn drop(&mut self) {
+        self.requests.borrow_mut().cancel_all();
         LOCAL_AGENTS_POOL.with(|pool| {
             let terminate_worker = {
                 if let Some(launched) = pool.borrow_mut().get_mut::<LocalAgent<AGN>>() {
@@ -288,46 +813,73 @@ impl<AGN: Agent> Drop for ContextBridge<AGN> {
 pub struct Job;
 
 impl Discoverer for Job {
-    fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
         let scope = AgentScope::<AGN>::new();
-        let responder = CallbackResponder { callback };
+        let requests = Requests::new();
+        let id = HandlerId::new(SINGLETON_ID, callback.is_some());
+        let responder = CallbackResponder {
+            callback,
+            requests: requests.clone(),
+        };
         let agent_link = AgentLink::connect(&scope, responder);
         let upd = AgentUpdate::Create(agent_link);
         scope.send(upd);
-        let upd = AgentUpdate::Connected(SINGLETON_ID);
+        let upd = AgentUpdate::Connected(id);
         scope.send(upd);
-        let bridge = JobBridge { scope };
+        let bridge = JobBridge { scope, id, requests };
         Box::new(bridge)
     }
 }
 
-const SINGLETON_ID: HandlerId = HandlerId(0);
+/// Id of the only one bridge of an agent.
+const SINGLETON_ID: usize = 0;
 
 struct CallbackResponder<AGN: Agent> {
-    callback: Callback<AGN::Output>,
+    callback: Option<Callback<AGN::Output>>,
+    requests: Shared<Requests<AGN::Output>>,
 }
 
 impl<AGN: Agent> Responder<AGN> for CallbackResponder<AGN> {
-    fn response(&self, id: HandlerId, output: AGN::Output) {
-        assert_eq!(id.raw_id(), SINGLETON_ID.raw_id());
-        self.callback.emit(output);
+    fn response(&self, _id: HandlerId, output: AGN::Output) {
+        if let Some(ref callback) = self.callback {
+            callback.emit(output);
+        }
+    }
+
+    fn respond(&self, request: RequestId, output: AGN::Output) {
+        Requests::resolve(&self.requests, request.serial, output);
     }
 }
 
 struct JobBridge<AGN: Agent> {
     scope: AgentScope<AGN>,
+    id: HandlerId,
+    requests: Shared<Requests<AGN::Output>>,
 }
 
 impl<AGN: Agent> Bridge<AGN> for JobBridge<AGN> {
     fn send(&mut self, msg: AGN::Input) {
-        let upd = AgentUpdate::Input(msg, SINGLETON_ID);
+        let upd = AgentUpdate::Input(msg, self.id);
+        self.scope.send(upd);
+    }
+
+    fn request(
+        &mut self,
+        msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        timeout: Option<Duration>,
+    ) -> RequestTask {
+        let (request, task) = Requests::add(&self.requests, self.id, callback, timeout);
+        let upd = AgentUpdate::Request(msg, request);
         self.scope.send(upd);
+        task
     }
 }
 
 impl<AGN: Agent> Drop for JobBridge<AGN> {
     fn drop(&mut self) {
-        let upd = AgentUpdate::Disconnected(SINGLETON_ID);
+        self.requests.borrow_mut().cancel_all();
+        let upd = AgentUpdate::Disconnected(self.id);
         self.scope.send(upd);
         let upd = AgentUpdate::Destroy;
         self.scope.send(upd);
@@ -340,82 +892,506 @@ impl<AGN: Agent> Drop for JobBridge<AGN> {
 pub struct Private;
 
 impl Discoverer for Private {
-    fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
-        let handler = move |data: Vec<u8>| {
-            let msg = FromWorker::<AGN::Output>::unpack(&data);
-            match msg {
-                FromWorker::WorkerLoaded => {
-                    // TODO Send `Connected` message
-                },
-                FromWorker::ProcessOutput(id, output) => {
-                    assert_eq!(id.raw_id(), SINGLETON_ID.raw_id());
-                    callback.emit(output);
-                },
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
+        let worker = QueuedWorker::spawn(AGN::name_of_resource(), AGN::restart_policy());
+        let requests = Requests::new();
+        let id = HandlerId::new(SINGLETON_ID, callback.is_some());
+        let handler = {
+            let worker = worker.clone();
+            let requests = requests.clone();
+            move |packet: Packet| {
+                // Private worker has the only one bridge
+                match unpack_output::<AGN>(&worker, packet) {
+                    Some(FromWorker::ProcessOutput(_, output)) => {
+                        if let Some(ref callback) = callback {
+                            callback.emit(output);
+                        }
+                    },
+                    Some(FromWorker::ProcessResponse(request, output)) => {
+                        Requests::resolve(&requests, request.serial, output);
+                    },
+                    _ => { },
+                }
             }
         };
-        // TODO Need somethig better...
-        let name_of_resource = AGN::name_of_resource();
-        let worker = js! {
-            var worker = new Worker(@{name_of_resource});
-            var handler = @{handler};
-            worker.onmessage = function(event) {
-                handler(event.data);
-            };
-            return worker;
-        };
+        QueuedWorker::set_handler(&worker, handler);
+        let msg = ToWorker::<AGN::Input>::Connected(id).pack(AGN::codec());
+        worker.borrow_mut().add_bridge(id, msg);
         let bridge = PrivateBridge {
             worker,
+            id,
+            requests,
             _agent: PhantomData,
         };
         Box::new(bridge)
     }
 }
 
+impl Remote for Private {}
+
 /// A connection manager for components interaction with workers.
 pub struct PrivateBridge<T: Agent> {
-    worker: Value,
+    worker: Shared<QueuedWorker>,
+    id: HandlerId,
+    requests: Shared<Requests<T::Output>>,
     _agent: PhantomData<T>,
 }
 
 impl<AGN: Agent> Bridge<AGN> for PrivateBridge<AGN> {
     fn send(&mut self, msg: AGN::Input) {
-        // TODO Important! Implement.
-        // Use a queue to collect a messages if an instance is not ready
-        // and send them to an agent when it will reported readiness.
-        let msg = ToWorker::ProcessInput(SINGLETON_ID, msg).pack();
-        let worker = &self.worker;
-        js! {
-            var worker = @{worker};
-            var bytes = @{msg};
-            worker.postMessage(bytes);
-        };
+        let msg = ToWorker::ProcessInput(self.id, msg).pack(AGN::codec());
+        self.worker.borrow_mut().send_input(self.id, msg);
+    }
+
+    fn request(
+        &mut self,
+        msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        timeout: Option<Duration>,
+    ) -> RequestTask {
+        let (request, task) = Requests::add(&self.requests, self.id, callback, timeout);
+        let msg = ToWorker::ProcessRequest(request, msg).pack(AGN::codec());
+        self.worker.borrow_mut().send_input(self.id, msg);
+        task
+    }
+
+    fn set_error_handler(&mut self, callback: Callback<AgentError>) {
+        self.worker.borrow_mut().set_error_handler(self.id, callback);
     }
 }
 
 impl<AGN: Agent> Drop for PrivateBridge<AGN> {
     fn drop(&mut self) {
-        // TODO Send `Destroy` message.
+        self.requests.borrow_mut().cancel_all();
+        self.worker.borrow_mut().disconnect::<AGN>(self.id, true);
     }
 }
 
-struct RemoteAgent<AGN: Agent> {
+/// A worker which keeps outgoing messages in a queue
+/// until the worker reports that it has loaded.
+///
+/// It also watches for errors of the worker and respawns it
+/// according to the `RestartPolicy` of the agent.
+struct QueuedWorker {
+    name_of_resource: &'static str,
+    shared: bool,
+    policy: RestartPolicy,
+    restarts: u32,
     worker: Value,
-    slab: Shared<Slab<Callback<AGN::Output>>>,
+    /// JS functions which are attached to the worker, kept to attach them to a respawned one.
+    listeners: Value,
+    loaded: bool,
+    crashed: bool,
+    queue: Vec<Packet>,
+    /// Packed `Connected` messages of live bridges which are repeated after a restart.
+    bridges: Vec<(HandlerId, Packet)>,
+    /// `true` if the worker reports processed inputs (see `counts_inputs`).
+    counted: bool,
+    in_flight: HashMap<HandlerId, usize>,
+    errors: HashMap<HandlerId, Callback<AgentError>>,
+}
+
+impl QueuedWorker {
+    fn spawn(name_of_resource: &'static str, policy: RestartPolicy) -> Shared<Self> {
+        QueuedWorker::new(name_of_resource, false, policy, false)
+    }
+
+    /// Spawns a worker of a pool which reports processed inputs to balance the load.
+    fn spawn_balanced(name_of_resource: &'static str, policy: RestartPolicy) -> Shared<Self> {
+        QueuedWorker::new(name_of_resource, false, policy, true)
+    }
+
+    /// Connects to a shared worker. Messages are sent through its port.
+    /// Shared workers are never respawned: other tabs could still be connected
+    /// to the failed one and a new connection would join it again.
+    fn connect(name_of_resource: &'static str, policy: RestartPolicy) -> Shared<Self> {
+        if policy != RestartPolicy::Never {
+            warn!("Shared worker agent {} can't be restarted, the restart policy is ignored",
+                  name_of_resource);
+        }
+        QueuedWorker::new(name_of_resource, true, RestartPolicy::Never, false)
+    }
+
+    fn new(
+        name_of_resource: &'static str,
+        shared: bool,
+        policy: RestartPolicy,
+        balanced: bool,
+    ) -> Shared<Self> {
+        let worker = QueuedWorker {
+            name_of_resource,
+            shared,
+            policy,
+            restarts: 0,
+            worker: start_worker(name_of_resource, shared),
+            listeners: Value::Undefined,
+            loaded: false,
+            crashed: false,
+            queue: Vec::new(),
+            bridges: Vec::new(),
+            counted: balanced || policy != RestartPolicy::Never,
+            in_flight: HashMap::new(),
+            errors: HashMap::new(),
+        };
+        Rc::new(RefCell::new(worker))
+    }
+
+    fn set_handler<F>(this: &Shared<Self>, handler: F)
+    where
+        F: Fn(Packet) + 'static,
+    {
+        let handler = move |data: Vec<u8>, buffers: Value| {
+            handler(Packet::receive(data, buffers));
+        };
+        let on_error = {
+            let worker = this.clone();
+            move |message: String| QueuedWorker::crashed(&worker, AgentErrorKind::Crashed, message)
+        };
+        let listeners = js!(
+            var handler = @{handler};
+            var onerror = @{on_error};
+            return {
+                handler: handler,
+                onerror: onerror,
+                message: function(event) {
+                    handler(event.data.bytes, event.data.buffers);
+                },
+                error: function(event) {
+                    // Prevents reporting of the error to the console twice
+                    if (event.preventDefault) {
+                        event.preventDefault();
+                    }
+                    onerror(event.message || "worker agent failed");
+                },
+            };
+        );
+        let mut worker = this.borrow_mut();
+        worker.listeners = listeners;
+        worker.attach();
+    }
+
+    fn attach(&self) {
+        js! { @(no_return)
+            var worker = @{&self.worker};
+            var listeners = @{&self.listeners};
+            // Errors of a shared worker are reported by its owner, not by the port
+            var owner = worker.yewOwner || worker;
+            worker.onmessage = listeners.message;
+            worker.onmessageerror = listeners.error;
+            owner.onerror = listeners.error;
+        };
+    }
+
+    fn detach(&self) {
+        js! { @(no_return)
+            var worker = @{&self.worker};
+            var owner = worker.yewOwner || worker;
+            worker.onmessage = null;
+            worker.onmessageerror = null;
+            owner.onerror = null;
+        };
+    }
+
+    /// Stops listening of messages from the worker.
+    /// The port of a shared worker is closed, other tabs could still use the worker.
+    fn release(&mut self) {
+        self.queue.clear();
+        self.detach();
+        if self.shared {
+            js! { @(no_return)
+                @{&self.worker}.close();
+            };
+        }
+        // The handler could be running now, because the last bridge
+        // is often dropped by its own callback, so closures are freed
+        // after the current call returns.
+        js! { @(no_return)
+            var listeners = @{&self.listeners};
+            if (listeners) {
+                setTimeout(function() {
+                    listeners.handler.drop();
+                    listeners.onerror.drop();
+                }, 0);
+            }
+        };
+        self.listeners = Value::Undefined;
+    }
+
+    /// Kills the worker immediately (or closes the port of a shared worker).
+    fn terminate(&mut self) {
+        self.release();
+        self.kill();
+    }
+
+    fn kill(&self) {
+        js! { @(no_return)
+            var worker = @{&self.worker};
+            if (worker.terminate) {
+                worker.terminate();
+            } else {
+                worker.close();
+            }
+        };
+    }
+
+    /// Sends a message or keeps it in the queue if the worker is not loaded yet.
+    /// Messages to a crashed worker are dropped.
+    fn post(&mut self, packet: Packet) {
+        if self.crashed {
+            return;
+        }
+        if self.loaded {
+            post_message(&self.worker, packet);
+        } else {
+            self.queue.push(packet);
+        }
+    }
+
+    /// Sends all collected messages in order.
+    fn set_loaded(&mut self) {
+        self.loaded = true;
+        for packet in self.queue.drain(..) {
+            post_message(&self.worker, packet);
+        }
+    }
+
+    /// Sends a `Connected` message of a bridge and keeps it to repeat after a restart.
+    fn add_bridge(&mut self, id: HandlerId, connected: Packet) {
+        self.bridges.push((id, connected.clone()));
+        self.post(connected);
+    }
+
+    fn remove_bridge(&mut self, id: HandlerId) {
+        self.bridges.retain(|&(bridge, _)| bridge != id);
+        self.in_flight.remove(&id);
+        self.errors.remove(&id);
+    }
+
+    /// Disconnects a bridge from the agent and stops the worker after the last bridge.
+    fn disconnect<AGN: Agent>(&mut self, id: HandlerId, last: Last) {
+        self.remove_bridge(id);
+        let msg = ToWorker::<AGN::Input>::Disconnected(id).pack(AGN::codec());
+        self.post(msg);
+        if last {
+            // The worker closes itself when the agent was destroyed
+            let msg = ToWorker::<AGN::Input>::Destroy.pack(AGN::codec());
+            self.post(msg);
+            if self.loaded && !self.crashed {
+                self.release();
+            } else {
+                // The agent was not created yet or the worker has crashed
+                self.terminate();
+            }
+        }
+    }
+
+    /// Returns the count of inputs which weren't processed by the worker yet.
+    fn load(&self) -> usize {
+        self.in_flight.values().sum()
+    }
+
+    fn set_error_handler(&mut self, id: HandlerId, callback: Callback<AgentError>) {
+        self.errors.insert(id, callback);
+    }
+
+    /// Sends an input and counts it until the worker reports that it was processed.
+    fn send_input(&mut self, id: HandlerId, packet: Packet) {
+        if self.crashed {
+            warn!("Input to the crashed worker agent was dropped: {}", self.name_of_resource);
+            return;
+        }
+        if self.counted {
+            *self.in_flight.entry(id).or_insert(0) += 1;
+        }
+        self.post(packet);
+    }
+
+    fn input_processed(&mut self, id: HandlerId) {
+        if let Some(count) = self.in_flight.get_mut(&id) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Kills the failed worker, respawns it if the policy allows that
+    /// and reports the error to bridges with the count of lost inputs.
+    /// Only crashed workers are respawned, a failed handshake would fail again.
+    fn crashed(this: &Shared<Self>, kind: AgentErrorKind, message: String) {
+        let (restarted, lost) = {
+            let mut worker = this.borrow_mut();
+            warn!("Worker agent {} failed: {}", worker.name_of_resource, message);
+            let restarted = kind == AgentErrorKind::Crashed && match worker.policy {
+                RestartPolicy::Never => false,
+                RestartPolicy::Always => true,
+                RestartPolicy::Limited(limit) => worker.restarts < limit,
+            };
+            worker.detach();
+            worker.kill();
+            // Inputs are still in the queue if the worker was not loaded
+            let lost = if worker.loaded || !restarted {
+                mem::take(&mut worker.in_flight)
+            } else {
+                HashMap::new()
+            };
+            if restarted {
+                worker.restarts += 1;
+                worker.respawn();
+            } else {
+                worker.crashed = true;
+                worker.queue.clear();
+            }
+            (restarted, lost)
+        };
+        QueuedWorker::notify(this, kind, &message, restarted, lost);
+    }
+
+    /// Reports an error which doesn't break the worker.
+    fn report(this: &Shared<Self>, kind: AgentErrorKind, message: String) {
+        warn!("Worker agent {} reported: {}", this.borrow().name_of_resource, message);
+        QueuedWorker::notify(this, kind, &message, false, HashMap::new());
+    }
+
+    fn notify(
+        this: &Shared<Self>,
+        kind: AgentErrorKind,
+        message: &str,
+        restarted: bool,
+        mut lost: HashMap<HandlerId, usize>,
+    ) {
+        let notifications: Vec<_> = this.borrow().errors
+            .iter()
+            .map(|(id, callback)| {
+                let error = AgentError {
+                    kind,
+                    message: message.to_owned(),
+                    restarted,
+                    lost_inputs: lost.remove(id).unwrap_or(0),
+                };
+                (callback.clone(), error)
+            })
+            .collect();
+        for (callback, error) in notifications {
+            callback.emit(error);
+        }
+    }
+
+    /// Starts a new worker and connects live bridges to it again.
+    fn respawn(&mut self) {
+        self.worker = start_worker(self.name_of_resource, self.shared);
+        self.attach();
+        if self.loaded {
+            self.loaded = false;
+            self.queue = self.bridges
+                .iter()
+                .map(|(_, connected)| connected.clone())
+                .collect();
+        }
+    }
+}
+
+fn start_worker(name_of_resource: &str, shared: bool) -> Value {
+    if shared {
+        js!(
+            var worker = new SharedWorker(@{name_of_resource});
+            var port = worker.port;
+            port.yewOwner = worker;
+            return port;
+        )
+    } else {
+        js!(
+            return new Worker(@{name_of_resource});
+        )
+    }
+}
+
+/// Decodes a message from a worker and returns an output or a response for a bridge.
+/// Service messages are handled here: a worker which fails the handshake
+/// is stopped and decoding errors are reported to bridges.
+fn unpack_output<AGN: Agent>(
+    worker: &Shared<QueuedWorker>,
+    packet: Packet,
+) -> Option<FromWorker<AGN::Output>> {
+    match FromWorker::<AGN::Output>::unpack(packet, AGN::codec()) {
+        Ok(FromWorker::WorkerLoaded { envelope, protocol }) => {
+            let expected = (ENVELOPE_VERSION, AGN::protocol_version());
+            if (envelope, protocol) == expected {
+                worker.borrow_mut().set_loaded();
+            } else {
+                let message = format!(
+                    "worker uses envelope {} and protocol {}, but {} and {} expected",
+                    envelope, protocol, expected.0, expected.1,
+                );
+                QueuedWorker::crashed(worker, AgentErrorKind::Handshake, message);
+            }
+            None
+        },
+        Ok(msg @ FromWorker::ProcessOutput(..)) | Ok(msg @ FromWorker::ProcessResponse(..)) => Some(msg),
+        Ok(FromWorker::InputProcessed(id)) => {
+            worker.borrow_mut().input_processed(id);
+            None
+        },
+        Ok(FromWorker::DecodeFailed(reason)) => {
+            QueuedWorker::report(worker, AgentErrorKind::Decode, reason);
+            None
+        },
+        Ok(FromWorker::WorkerFailed(message)) => {
+            QueuedWorker::crashed(worker, AgentErrorKind::Crashed, message);
+            None
+        },
+        Err(err) => {
+            let loaded = worker.borrow().loaded;
+            if loaded {
+                QueuedWorker::report(worker, AgentErrorKind::Decode, err.to_string());
+            } else {
+                // The worker uses another codec if it can't even say that it has loaded
+                let message = format!("can't decode a handshake of the worker: {}", err);
+                QueuedWorker::crashed(worker, AgentErrorKind::Handshake, message);
+            }
+            None
+        },
+    }
+}
+
+/// Sends a packet to a worker or a port and moves its buffers with the transfer list.
+fn post_message(worker: &Value, packet: Packet) {
+    js! {
+        var worker = @{worker};
+        var bytes = @{packet.data};
+        var buffers = @{packet.buffers};
+        worker.postMessage({ bytes: bytes, buffers: buffers }, buffers);
+    };
+}
+
+/// Sends a packet from a worker to its owner.
+fn post_to_parent(packet: Packet) {
+    let parent = js!( return self; );
+    post_message(&parent, packet);
+}
+
+struct RemoteAgent<AGN: Agent> {
+    worker: Shared<QueuedWorker>,
+    slab: Shared<Slab<Subscriber<AGN::Output>>>,
 }
 
 impl<AGN: Agent> RemoteAgent<AGN> {
-    pub fn new(worker: &Value, slab: Shared<Slab<Callback<AGN::Output>>>) -> Self {
+    pub fn new(worker: &Shared<QueuedWorker>, slab: Shared<Slab<Subscriber<AGN::Output>>>) -> Self {
         RemoteAgent {
             worker: worker.clone(),
             slab,
         }
     }
 
-    fn create_bridge(&mut self, callback: Callback<AGN::Output>) -> PublicBridge<AGN> {
-        let id = self.slab.borrow_mut().insert(callback);
+    fn create_bridge(&mut self, callback: Option<Callback<AGN::Output>>) -> PublicBridge<AGN> {
+        let requests = Requests::new();
+        let respondable = callback.is_some();
+        let id = self.slab.borrow_mut().insert(Subscriber::new(callback, &requests));
+        let id = HandlerId::new(id, respondable);
+        // It's sent to the worker after `WorkerLoaded` if it's still loading
+        let msg = ToWorker::<AGN::Input>::Connected(id).pack(AGN::codec());
+        self.worker.borrow_mut().add_bridge(id, msg);
         PublicBridge {
             worker: self.worker.clone(),
-            id: id.into(),
+            id,
+            requests,
             _agent: PhantomData,
         }
     }
@@ -435,44 +1411,159 @@ thread_local! {
 pub struct Public;
 
 impl Discoverer for Public {
-    fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
+        spawn_or_join_remote::<AGN>(callback, QueuedWorker::spawn)
+    }
+}
+
+/// Joins a worker which is spawned once per tab and routes outputs by ids of bridges.
+fn spawn_or_join_remote<AGN: Agent>(
+    callback: Option<Callback<AGN::Output>>,
+    spawn: fn(&'static str, RestartPolicy) -> Shared<QueuedWorker>,
+) -> Box<dyn Bridge<AGN>> {
+    let bridge = REMOTE_AGENTS_POOL.with(|pool| {
+        match pool.borrow_mut().entry::<RemoteAgent<AGN>>() {
+            Entry::Occupied(mut entry) => {
+                // TODO Insert callback!
+                entry.get_mut().create_bridge(callback)
+            },
+            Entry::Vacant(entry) => {
+                let slab_base: Shared<Slab<Subscriber<AGN::Output>>> =
+                    Rc::new(RefCell::new(Slab::new()));
+                let worker = spawn(AGN::name_of_resource(), AGN::restart_policy());
+                let handler = route_to_subscribers::<AGN>(&worker, &slab_base);
+                QueuedWorker::set_handler(&worker, handler);
+                let launched = RemoteAgent::new(&worker, slab_base);
+                entry.insert(launched).create_bridge(callback)
+            },
+        }
+    });
+    Box::new(bridge)
+}
+
+/// Creates a handler of messages of a worker which routes outputs
+/// and responses to subscribers by ids of bridges.
+fn route_to_subscribers<AGN: Agent>(
+    worker: &Shared<QueuedWorker>,
+    slab: &Shared<Slab<Subscriber<AGN::Output>>>,
+) -> impl Fn(Packet) {
+    let worker = worker.clone();
+    let slab = slab.clone();
+    move |packet: Packet| {
+        match unpack_output::<AGN>(&worker, packet) {
+            Some(FromWorker::ProcessOutput(id, output)) => {
+                let callback = slab.borrow()
+                    .get(id.raw_id())
+                    .and_then(|subscriber| subscriber.callback.clone());
+                if let Some(callback) = callback {
+                    callback.emit(output);
+                } else {
+                    warn!("Id of handler for remote worker not exists <slab>: {}", id.raw_id());
+                }
+            },
+            Some(FromWorker::ProcessResponse(request, output)) => {
+                let id = request.handler();
+                let requests = slab.borrow()
+                    .get(id.raw_id())
+                    .map(|subscriber| subscriber.requests.clone());
+                if let Some(requests) = requests {
+                    Requests::resolve(&requests, request.serial, output);
+                } else {
+                    warn!("Id of handler for remote worker not exists <slab>: {}", id.raw_id());
+                }
+            },
+            _ => { },
+        }
+    }
+}
+
+/// A connection manager for components interaction with workers.
+pub struct PublicBridge<T: Agent> {
+    worker: Shared<QueuedWorker>,
+    id: HandlerId,
+    requests: Shared<Requests<T::Output>>,
+    _agent: PhantomData<T>,
+}
+
+impl<AGN: Agent> Bridge<AGN> for PublicBridge<AGN> {
+    fn send(&mut self, msg: AGN::Input) {
+        let msg = ToWorker::ProcessInput(self.id, msg).pack(AGN::codec());
+        self.worker.borrow_mut().send_input(self.id, msg);
+    }
+
+    fn request(
+        &mut self,
+        msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        timeout: Option<Duration>,
+    ) -> RequestTask {
+        let (request, task) = Requests::add(&self.requests, self.id, callback, timeout);
+        let msg = ToWorker::ProcessRequest(request, msg).pack(AGN::codec());
+        self.worker.borrow_mut().send_input(self.id, msg);
+        task
+    }
+
+    fn set_error_handler(&mut self, callback: Callback<AgentError>) {
+        self.worker.borrow_mut().set_error_handler(self.id, callback);
+    }
+}
+
+impl<AGN: Agent> Drop for PublicBridge<AGN> {
+    fn drop(&mut self) {
+        self.requests.borrow_mut().cancel_all();
+        REMOTE_AGENTS_POOL.with(|pool| {
+            let terminate_worker = {
+                if let Some(launched) = pool.borrow_mut().get_mut::<RemoteAgent<AGN>>() {
+                    launched.remove_bridge(self)
+                } else {
+                    false
+                }
+            };
+            self.worker.borrow_mut().disconnect::<AGN>(self.id, terminate_worker);
+            if terminate_worker {
+                pool.borrow_mut().remove::<RemoteAgent<AGN>>();
+            }
+        });
+    }
+}
+
+
+impl Remote for Public {}
+
+/// Create a pool of workers in a tab. Every input is handled by the least loaded
+/// worker, so agents of this reach shouldn't keep a state between inputs.
+pub struct Pool;
+
+/// Count of workers in a pool if a browser doesn't report count of processors.
+const DEFAULT_POOL_SIZE: usize = 4;
+
+impl Discoverer for Pool {
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
         let bridge = REMOTE_AGENTS_POOL.with(|pool| {
-            match pool.borrow_mut().entry::<RemoteAgent<AGN>>() {
+            match pool.borrow_mut().entry::<PoolAgent<AGN>>() {
                 Entry::Occupied(mut entry) => {
-                    // TODO Insert callback!
                     entry.get_mut().create_bridge(callback)
                 },
                 Entry::Vacant(entry) => {
-                    let slab_base: Shared<Slab<Callback<AGN::Output>>> =
+                    let slab: Shared<Slab<Subscriber<AGN::Output>>> =
                         Rc::new(RefCell::new(Slab::new()));
-                    let slab = slab_base.clone();
-                    let handler = move |data: Vec<u8>| {
-                        let msg = FromWorker::<AGN::Output>::unpack(&data);
-                        match msg {
-                            FromWorker::WorkerLoaded => {
-                                // TODO Use `AtomicBool` lock to check its loaded
-                                // TODO Send `Connected` message
-                            },
-                            FromWorker::ProcessOutput(id, output) => {
-                                let callback = slab.borrow().get(id.raw_id()).cloned();
-                                if let Some(callback) = callback {
-                                    callback.emit(output);
-                                } else {
-                                    warn!("Id of handler for remote worker not exists <slab>: {}", id.raw_id());
-                                }
-                            },
-                        }
-                    };
-                    let name_of_resource = AGN::name_of_resource();
-                    let worker = js! {
-                        var worker = new Worker(@{name_of_resource});
-                        var handler = @{handler};
-                        worker.onmessage = function(event) {
-                            handler(event.data);
-                        };
-                        return worker;
+                    let size = AGN::pool_size().unwrap_or_else(hardware_concurrency).max(1);
+                    let workers = (0..size)
+                        .map(|_| {
+                            let worker = QueuedWorker::spawn_balanced(
+                                AGN::name_of_resource(),
+                                AGN::restart_policy(),
+                            );
+                            let handler = route_to_subscribers::<AGN>(&worker, &slab);
+                            QueuedWorker::set_handler(&worker, handler);
+                            worker
+                        })
+                        .collect();
+                    let launched = PoolAgent {
+                        workers: Rc::new(workers),
+                        cursor: Rc::new(Cell::new(0)),
+                        slab,
                     };
-                    let launched = RemoteAgent::new(&worker, slab_base);
                     entry.insert(launched).create_bridge(callback)
                 },
             }
@@ -481,61 +1572,392 @@ impl Discoverer for Public {
     }
 }
 
-/// A connection manager for components interaction with workers.
-pub struct PublicBridge<T: Agent> {
-    worker: Value,
+impl Remote for Pool {
+    fn balances_inputs() -> bool {
+        true
+    }
+}
+
+fn hardware_concurrency() -> usize {
+    let count: Option<u32> = js!( return navigator.hardwareConcurrency; ).try_into().ok();
+    count.map(|count| count as usize).unwrap_or(DEFAULT_POOL_SIZE)
+}
+
+struct PoolAgent<AGN: Agent> {
+    workers: Rc<Vec<Shared<QueuedWorker>>>,
+    /// Index of a worker to start a search of the least loaded one from.
+    cursor: Rc<Cell<usize>>,
+    slab: Shared<Slab<Subscriber<AGN::Output>>>,
+}
+
+impl<AGN: Agent> PoolAgent<AGN> {
+    /// Creates a bridge which is connected to all workers of the pool.
+    fn create_bridge(&mut self, callback: Option<Callback<AGN::Output>>) -> PoolBridge<AGN> {
+        let requests = Requests::new();
+        let respondable = callback.is_some();
+        let id = self.slab.borrow_mut().insert(Subscriber::new(callback, &requests));
+        let id = HandlerId::new(id, respondable);
+        for worker in self.workers.iter() {
+            let msg = ToWorker::<AGN::Input>::Connected(id).pack(AGN::codec());
+            worker.borrow_mut().add_bridge(id, msg);
+        }
+        PoolBridge {
+            workers: self.workers.clone(),
+            cursor: self.cursor.clone(),
+            id,
+            requests,
+            _agent: PhantomData,
+        }
+    }
+
+    fn remove_bridge(&mut self, bridge: &PoolBridge<AGN>) -> Last {
+        let mut slab = self.slab.borrow_mut();
+        let _ = slab.remove(bridge.id.raw_id());
+        slab.is_empty()
+    }
+}
+
+/// A connection manager for components interaction with a pool of workers.
+pub struct PoolBridge<T: Agent> {
+    workers: Rc<Vec<Shared<QueuedWorker>>>,
+    cursor: Rc<Cell<usize>>,
     id: HandlerId,
+    requests: Shared<Requests<T::Output>>,
     _agent: PhantomData<T>,
 }
 
-impl<AGN: Agent> PublicBridge<AGN> {
-    fn send_to_remote(&self, msg: ToWorker<AGN::Input>) {
-        // TODO Important! Implement.
-        // Use a queue to collect a messages if an instance is not ready
-        // and send them to an agent when it will reported readiness.
-        let msg = msg.pack();
-        let worker = &self.worker;
-        js! {
-            var worker = @{worker};
-            var bytes = @{msg};
-            worker.postMessage(bytes);
-        };
+impl<AGN: Agent> PoolBridge<AGN> {
+    /// Picks an alive worker with the least count of unprocessed inputs.
+    /// Equally loaded workers are picked in turn.
+    fn pick_worker(&self) -> &Shared<QueuedWorker> {
+        let count = self.workers.len();
+        let start = self.cursor.get();
+        let index = (start..start + count)
+            .map(|index| index % count)
+            .min_by_key(|&index| {
+                let worker = self.workers[index].borrow();
+                (worker.crashed, worker.load())
+            })
+            .expect("pool has no workers");
+        self.cursor.set((index + 1) % count);
+        &self.workers[index]
     }
 }
 
-impl<AGN: Agent> Bridge<AGN> for PublicBridge<AGN> {
+impl<AGN: Agent> Bridge<AGN> for PoolBridge<AGN> {
     fn send(&mut self, msg: AGN::Input) {
-        let msg = ToWorker::ProcessInput(self.id, msg);
-        self.send_to_remote(msg);
+        let msg = ToWorker::ProcessInput(self.id, msg).pack(AGN::codec());
+        self.pick_worker().borrow_mut().send_input(self.id, msg);
+    }
+
+    fn request(
+        &mut self,
+        msg: AGN::Input,
+        callback: ResponseCallback<AGN::Output>,
+        timeout: Option<Duration>,
+    ) -> RequestTask {
+        let (request, task) = Requests::add(&self.requests, self.id, callback, timeout);
+        let msg = ToWorker::ProcessRequest(request, msg).pack(AGN::codec());
+        self.pick_worker().borrow_mut().send_input(self.id, msg);
+        task
+    }
+
+    fn set_error_handler(&mut self, callback: Callback<AgentError>) {
+        for worker in self.workers.iter() {
+            worker.borrow_mut().set_error_handler(self.id, callback.clone());
+        }
     }
 }
 
-impl<AGN: Agent> Drop for PublicBridge<AGN> {
+impl<AGN: Agent> Drop for PoolBridge<AGN> {
     fn drop(&mut self) {
+        self.requests.borrow_mut().cancel_all();
         REMOTE_AGENTS_POOL.with(|pool| {
-            let terminate_worker = {
-                if let Some(launched) = pool.borrow_mut().get_mut::<RemoteAgent<AGN>>() {
+            let terminate_workers = {
+                if let Some(launched) = pool.borrow_mut().get_mut::<PoolAgent<AGN>>() {
                     launched.remove_bridge(self)
                 } else {
                     false
                 }
             };
-            let upd = ToWorker::Disconnected(self.id);
-            self.send_to_remote(upd);
-            if terminate_worker {
-                let upd = ToWorker::Destroy;
-                self.send_to_remote(upd);
-                pool.borrow_mut().remove::<RemoteAgent<AGN>>();
+            for worker in self.workers.iter() {
+                worker.borrow_mut().disconnect::<AGN>(self.id, terminate_workers);
+            }
+            if terminate_workers {
+                pool.borrow_mut().remove::<PoolAgent<AGN>>();
             }
         });
     }
 }
 
-
-/// Create a single instance in a browser.
+/// Create a single instance in a browser. The agent lives in a `SharedWorker`
+/// which is shared across tabs of the same origin.
 pub struct Global;
 
-impl Discoverer for Global { }
+impl Discoverer for Global {
+    fn spawn_or_join<AGN: Agent>(callback: Option<Callback<AGN::Output>>) -> Box<dyn Bridge<AGN>> {
+        spawn_or_join_remote::<AGN>(callback, QueuedWorker::connect)
+    }
+}
+
+impl Remote for Global {
+    fn register<AGN: Agent>() {
+        register_shared::<AGN>();
+    }
+}
+
+/// Routes of bridges of all tabs connected to a shared worker. Ids of bridges
+/// are unique inside of a tab only, an agent gets ids unique for the worker.
+#[derive(Default)]
+struct SharedRoutes {
+    ports: Slab<Value>,
+    handlers: Slab<(usize, HandlerId)>,
+}
+
+impl SharedRoutes {
+    fn find(&self, port: usize, id: HandlerId) -> Option<HandlerId> {
+        self.handlers
+            .iter()
+            .find(|&(_, route)| *route == (port, id))
+            .map(|(global, _)| HandlerId::new(global, id.is_respondable()))
+    }
+
+    fn route(&mut self, port: usize, id: HandlerId) -> HandlerId {
+        match self.find(port, id) {
+            Some(global) => global,
+            None => HandlerId::new(self.handlers.insert((port, id)), id.is_respondable()),
+        }
+    }
+
+    /// Removes a port with its routes and returns ids which were connected through it.
+    /// Returns `None` if the port was already removed.
+    fn close_port(&mut self, port: usize) -> Option<(Value, Vec<HandlerId>)> {
+        if !self.ports.contains(port) {
+            return None;
+        }
+        let ids: Vec<HandlerId> = self.handlers
+            .iter()
+            .filter(|&(_, route)| route.0 == port)
+            .map(|(global, route)| HandlerId::new(global, route.1.is_respondable()))
+            .collect();
+        for global in &ids {
+            self.handlers.remove(global.raw_id());
+        }
+        Some((self.ports.remove(port), ids))
+    }
+}
+
+/// Sends messages to tabs which are connected to a shared worker.
+#[derive(Clone)]
+struct PortsResponder {
+    routes: Shared<SharedRoutes>,
+    /// Removes a port of a tab and disconnects its bridges from the agent.
+    close_port: Rc<dyn Fn(usize)>,
+}
+
+impl PortsResponder {
+    /// Returns a port and an id of a bridge in its tab.
+    fn local_route(&self, id: HandlerId) -> Option<(usize, HandlerId)> {
+        let route = self.routes.borrow().handlers.get(id.raw_id()).cloned();
+        if route.is_none() {
+            warn!("Id of handler for shared worker not exists <routes>: {}", id.raw_id());
+        }
+        route
+    }
+
+    /// Sends a packet to a tab and closes the port if it can't be used anymore.
+    fn post(&self, port: usize, packet: Packet) {
+        let target = self.routes.borrow().ports.get(port).cloned();
+        if let Some(target) = target {
+            if !post_to_port(&target, packet) {
+                warn!("Can't send a message to a tab, its port is closed: {}", port);
+                (self.close_port)(port);
+            }
+        }
+    }
+}
+
+impl<AGN: Agent> Responder<AGN> for PortsResponder {
+    fn response(&self, id: HandlerId, output: AGN::Output) {
+        if let Some((port, local)) = self.local_route(id) {
+            let msg = FromWorker::ProcessOutput(local, output);
+            self.post(port, msg.pack(AGN::codec()));
+        }
+    }
+
+    fn respond(&self, request: RequestId, output: AGN::Output) {
+        if let Some((port, local)) = self.local_route(request.handler) {
+            let request = RequestId {
+                handler: local,
+                serial: request.serial,
+            };
+            let msg = FromWorker::ProcessResponse(request, output);
+            self.post(port, msg.pack(AGN::codec()));
+        }
+    }
+}
+
+/// Sends a packet to a port of a tab. Returns `false` if the port is unusable.
+fn post_to_port(port: &Value, packet: Packet) -> bool {
+    let posted = js! (
+        var port = @{port};
+        var bytes = @{packet.data};
+        var buffers = @{packet.buffers};
+        try {
+            port.postMessage({ bytes: bytes, buffers: buffers }, buffers);
+            return true;
+        } catch (e) {
+            return false;
+        }
+    );
+    posted.try_into().unwrap_or(false)
+}
+
+/// Reports panics of the agent to every tab and closes the shared worker,
+/// because errors of a shared worker are not delivered to its owners.
+fn report_panics<AGN: Agent>() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let failed = FromWorker::<AGN::Output>::WorkerFailed(info.to_string());
+        let packet = failed.pack(AGN::codec());
+        js! { @(no_return)
+            var bytes = @{packet.data};
+            var buffers = @{packet.buffers};
+            // The agent can't be used after a panic, so ports are taken from JS side
+            self.yewPorts.forEach(function(port) {
+                try {
+                    port.postMessage({ bytes: bytes, buffers: buffers });
+                } catch (e) { }
+            });
+            self.close();
+        };
+        previous(info);
+    }));
+}
+
+/// Runs an agent in a shared worker. Every tab connects with its own port.
+fn register_shared<AGN: Agent>() {
+    let scope = AgentScope::<AGN>::new();
+    let routes: Shared<SharedRoutes> = Rc::new(RefCell::new(SharedRoutes::default()));
+    let close_port = {
+        let scope = scope.clone();
+        let routes = routes.clone();
+        move |port: usize| {
+            let closed = routes.borrow_mut().close_port(port);
+            let (target, ids) = match closed {
+                Some(closed) => closed,
+                // The port was already closed
+                None => return,
+            };
+            js! { @(no_return)
+                var port = @{target};
+                self.yewPorts.delete(port);
+                port.close();
+            };
+            for id in ids {
+                scope.send(AgentUpdate::Disconnected(id));
+            }
+            if routes.borrow().ports.is_empty() {
+                scope.send(AgentUpdate::Destroy);
+                js! {
+                    // Terminates shared worker
+                    self.close();
+                };
+            }
+        }
+    };
+    let ports = PortsResponder {
+        routes: routes.clone(),
+        close_port: Rc::new(close_port),
+    };
+    let link = AgentLink::connect(&scope, ports.clone());
+    let upd = AgentUpdate::Create(link);
+    scope.send(upd);
+    report_panics::<AGN>();
+    let loaded: FromWorker<AGN::Output> = FromWorker::WorkerLoaded {
+        envelope: ENVELOPE_VERSION,
+        protocol: AGN::protocol_version(),
+    };
+    let loaded = loaded.pack(AGN::codec());
+    let connect = {
+        let ports = ports.clone();
+        move |target: Value| -> u32 {
+            let port = ports.routes.borrow_mut().ports.insert(target);
+            ports.post(port, loaded.clone());
+            port as u32
+        }
+    };
+    let closed = {
+        let ports = ports.clone();
+        move |port: u32| (ports.close_port)(port as usize)
+    };
+    let handler = move |port: u32, data: Vec<u8>, buffers: Value| {
+        let port = port as usize;
+        let packet = Packet::receive(data, buffers);
+        let msg = match ToWorker::<AGN::Input>::unpack(packet, AGN::codec()) {
+            Ok(msg) => msg,
+            Err(err) => {
+                warn!("Can't decode a message to the shared agent: {}", err);
+                let failed = FromWorker::<AGN::Output>::DecodeFailed(err.to_string());
+                ports.post(port, failed.pack(AGN::codec()));
+                return;
+            },
+        };
+        match msg {
+            ToWorker::Connected(id) => {
+                let id = routes.borrow_mut().route(port, id);
+                scope.send(AgentUpdate::Connected(id));
+            },
+            ToWorker::ProcessInput(local, value) => {
+                let id = routes.borrow_mut().route(port, local);
+                scope.send(AgentUpdate::Input(value, id));
+            },
+            ToWorker::ProcessRequest(request, value) => {
+                let id = routes.borrow_mut().route(port, request.handler);
+                let request = RequestId {
+                    handler: id,
+                    serial: request.serial,
+                };
+                scope.send(AgentUpdate::Request(value, request));
+            },
+            ToWorker::Disconnected(id) => {
+                let id = {
+                    let mut routes = routes.borrow_mut();
+                    let global = routes.find(port, id);
+                    if let Some(global) = global {
+                        routes.handlers.remove(global.raw_id());
+                    }
+                    global
+                };
+                if let Some(id) = id {
+                    scope.send(AgentUpdate::Disconnected(id));
+                }
+            },
+            ToWorker::Destroy => {
+                // The last bridge of a tab was dropped
+                (ports.close_port)(port);
+            },
+        }
+    };
+    js! {
+        var connect = @{connect};
+        var closed = @{closed};
+        var handler = @{handler};
+        self.yewPorts = new Set();
+        self.onconnect = function(event) {
+            var port = event.ports[0];
+            self.yewPorts.add(port);
+            var id = connect(port);
+            port.onmessage = function(event) {
+                handler(id, event.data.bytes, event.data.buffers);
+            };
+            // Fired when a tab was closed or has navigated away
+            port.onclose = function() {
+                closed(id);
+            };
+        };
+    };
+}
 
 /// Declares the behavior of the agent.
 pub trait Agent: Sized + 'static {
@@ -560,6 +1982,12 @@ pub trait Agent: Sized + 'static {
     /// This method called on every incoming message.
     fn handle(&mut self, msg: Self::Input, id: HandlerId);
 
+    /// This method called on every request. It calls `handle` by default and
+    /// the first response to the bridge during the call responds to the request.
+    fn handle_request(&mut self, msg: Self::Input, request: RequestId) {
+        self.handle(msg, request.handler());
+    }
+
     /// This method called on when a new bridge destroyed.
     fn disconnected(&mut self, _id: HandlerId) { }
 
@@ -570,18 +1998,38 @@ pub trait Agent: Sized + 'static {
     /// have to live in a separate files.
     fn name_of_resource() -> &'static str { "main.js" }
 
+    /// Count of workers of `Pool` reach. It's `navigator.hardwareConcurrency` if `None`.
+    fn pool_size() -> Option<usize> { None }
+
+    /// Tells what to do when a worker of the agent has crashed.
+    /// Bridges are connected to a respawned worker again.
+    /// `Global` agents are never respawned.
+    fn restart_policy() -> RestartPolicy { RestartPolicy::Never }
+
+    /// Wire format of messages of the agent.
+    fn codec() -> Codec { Codec::Bincode }
+
+    /// Version of `Input` and `Output` messages. A bridge refuses to work
+    /// with a worker which reports another version.
+    fn protocol_version() -> u32 { 0 }
 
 }
 
 /// This sctruct holds a reference to a component and to a global scheduler.
 pub struct AgentScope<AGN: Agent> {
     shared_agent: Shared<AgentRunnable<AGN>>,
+    /// A request which is handled by the agent now.
+    current_request: Rc<Cell<Option<RequestId>>>,
+    /// Handlers which are connected to the agent.
+    connected: Shared<Vec<HandlerId>>,
 }
 
 impl<AGN: Agent> Clone for AgentScope<AGN> {
     fn clone(&self) -> Self {
         AgentScope {
             shared_agent: self.shared_agent.clone(),
+            current_request: self.current_request.clone(),
+            connected: self.connected.clone(),
         }
     }
 }
@@ -589,12 +2037,14 @@ impl<AGN: Agent> Clone for AgentScope<AGN> {
 impl<AGN: Agent> AgentScope<AGN> {
     fn new() -> Self {
         let shared_agent = Rc::new(RefCell::new(AgentRunnable::new()));
-        AgentScope { shared_agent }
+        let current_request = Rc::new(Cell::new(None));
+        let connected = Rc::new(RefCell::new(Vec::new()));
+        AgentScope { shared_agent, current_request, connected }
     }
 
     fn send(&self, update: AgentUpdate<AGN>) {
         let envelope = AgentEnvelope {
-            shared_agent: self.shared_agent.clone(),
+            scope: self.clone(),
             message: Some(update),
         };
         let runnable: Box<dyn Runnable> = Box::new(envelope);
@@ -604,6 +2054,7 @@ impl<AGN: Agent> AgentScope<AGN> {
 
 trait Responder<AGN: Agent> {
     fn response(&self, id: HandlerId, output: AGN::Output);
+    fn respond(&self, request: RequestId, output: AGN::Output);
 }
 
 struct WorkerResponder {
@@ -612,11 +2063,12 @@ struct WorkerResponder {
 impl<AGN: Agent> Responder<AGN> for WorkerResponder {
     fn response(&self, id: HandlerId, output: AGN::Output) {
         let msg = FromWorker::ProcessOutput(id, output);
-        let data = msg.pack();
-        js! {
-            var data = @{data};
-            self.postMessage(data);
-        };
+        post_to_parent(msg.pack(AGN::codec()));
+    }
+
+    fn respond(&self, request: RequestId, output: AGN::Output) {
+        let msg = FromWorker::ProcessResponse(request, output);
+        post_to_parent(msg.pack(AGN::codec()));
     }
 }
 
@@ -638,9 +2090,48 @@ impl<AGN: Agent> AgentLink<AGN> {
         }
     }
 
-    /// Send response to an actor.
+    /// Send response to an actor. The first response to the bridge of a request
+    /// which is handled now responds to the request. Outputs to dispatchers are dropped.
     pub fn response(&self, id: HandlerId, output: AGN::Output) {
-        self.responder.response(id, output);
+        if !id.is_respondable() {
+            return;
+        }
+        match self.scope.current_request.get() {
+            Some(request) if request.handler == id => {
+                self.scope.current_request.set(None);
+                self.responder.respond(request, output);
+            },
+            _ => {
+                self.responder.response(id, output);
+            },
+        }
+    }
+
+    /// Responds to a request. Use it to respond to a request after `handle_request` call.
+    pub fn respond(&self, request: RequestId, output: AGN::Output) {
+        self.responder.respond(request, output);
+    }
+
+    /// Returns ids of all connected handlers including dispatchers.
+    pub fn connected(&self) -> Vec<HandlerId> {
+        self.scope.connected.borrow().clone()
+    }
+
+    /// Sends an output to all connected handlers except dispatchers.
+    /// Like `response`, it responds to the request which is handled now.
+    pub fn broadcast(&self, output: AGN::Output)
+    where
+        AGN::Output: Clone,
+    {
+        let subscribers: Vec<HandlerId> = self.scope.connected
+            .borrow()
+            .iter()
+            .filter(|id| id.is_respondable())
+            .cloned()
+            .collect();
+        for id in subscribers {
+            self.response(id, output.clone());
+        }
     }
 
     /// This method sends messages back to the component's loop.
@@ -678,12 +2169,13 @@ enum AgentUpdate<AGN: Agent> {
     Message(AGN::Message),
     Connected(HandlerId),
     Input(AGN::Input, HandlerId),
+    Request(AGN::Input, RequestId),
     Disconnected(HandlerId),
     Destroy,
 }
 
 struct AgentEnvelope<AGN: Agent> {
-    shared_agent: Shared<AgentRunnable<AGN>>,
+    scope: AgentScope<AGN>,
     message: Option<AgentUpdate<AGN>>,
 }
 
@@ -692,7 +2184,7 @@ where
     AGN: Agent,
 {
     fn run(&mut self) {
-        let mut this = self.shared_agent.borrow_mut();
+        let mut this = self.scope.shared_agent.borrow_mut();
         if this.destroyed {
             return;
         }
@@ -707,6 +2199,7 @@ where
                     .update(msg);
             }
             AgentUpdate::Connected(id) => {
+                self.scope.connected.borrow_mut().push(id);
                 this.agent.as_mut()
                     .expect("agent was not created to send a connected message")
                     .connected(id);
@@ -716,7 +2209,15 @@ where
                     .expect("agent was not created to process inputs")
                     .handle(inp, id);
             }
+            AgentUpdate::Request(inp, request) => {
+                let agent = this.agent.as_mut()
+                    .expect("agent was not created to process requests");
+                self.scope.current_request.set(Some(request));
+                agent.handle_request(inp, request);
+                self.scope.current_request.set(None);
+            }
             AgentUpdate::Disconnected(id) => {
+                self.scope.connected.borrow_mut().retain(|connected| *connected != id);
                 this.agent.as_mut()
                     .expect("agent was not created to send a disconnected message")
                     .disconnected(id);
@@ -729,3 +2230,124 @@ where
         }
     }
 }
+
+// <<< TEST HARNESS >>>
+
+/// Runs an agent in the current thread without workers and keeps its outputs
+/// for every handler. It allows to test agents natively with `cargo test`.
+pub struct AgentHarness<AGN: Agent> {
+    scope: AgentScope<AGN>,
+    outputs: Shared<Vec<(HandlerId, AGN::Output)>>,
+    responses: Shared<Vec<(RequestId, AGN::Output)>>,
+    next_id: usize,
+    next_serial: u32,
+}
+
+struct HarnessResponder<AGN: Agent> {
+    outputs: Shared<Vec<(HandlerId, AGN::Output)>>,
+    responses: Shared<Vec<(RequestId, AGN::Output)>>,
+}
+
+impl<AGN: Agent> Responder<AGN> for HarnessResponder<AGN> {
+    fn response(&self, id: HandlerId, output: AGN::Output) {
+        self.outputs.borrow_mut().push((id, output));
+    }
+
+    fn respond(&self, request: RequestId, output: AGN::Output) {
+        self.responses.borrow_mut().push((request, output));
+    }
+}
+
+impl<AGN: Agent> Default for AgentHarness<AGN> {
+    fn default() -> Self {
+        AgentHarness::new()
+    }
+}
+
+impl<AGN: Agent> AgentHarness<AGN> {
+    /// Creates an instance of the agent.
+    pub fn new() -> Self {
+        let scope = AgentScope::<AGN>::new();
+        let outputs = Rc::new(RefCell::new(Vec::new()));
+        let responses = Rc::new(RefCell::new(Vec::new()));
+        let responder = HarnessResponder {
+            outputs: outputs.clone(),
+            responses: responses.clone(),
+        };
+        let link = AgentLink::connect(&scope, responder);
+        scope.send(AgentUpdate::Create(link));
+        AgentHarness {
+            scope,
+            outputs,
+            responses,
+            next_id: 0,
+            next_serial: 0,
+        }
+    }
+
+    /// Connects a new handler which gets outputs like a bridge.
+    pub fn connect(&mut self) -> HandlerId {
+        self.connect_handler(true)
+    }
+
+    /// Connects a new handler which doesn't get outputs like a `Dispatcher`.
+    pub fn connect_dispatcher(&mut self) -> HandlerId {
+        self.connect_handler(false)
+    }
+
+    fn connect_handler(&mut self, respondable: bool) -> HandlerId {
+        let id = HandlerId::new(self.next_id, respondable);
+        self.next_id += 1;
+        self.scope.send(AgentUpdate::Connected(id));
+        id
+    }
+
+    /// Disconnects a handler like a dropped bridge.
+    pub fn disconnect(&mut self, id: HandlerId) {
+        self.scope.send(AgentUpdate::Disconnected(id));
+    }
+
+    /// Sends an input from a handler.
+    pub fn send(&mut self, id: HandlerId, msg: AGN::Input) {
+        self.scope.send(AgentUpdate::Input(msg, id));
+    }
+
+    /// Sends a request from a handler and returns its id to get a response.
+    pub fn request(&mut self, id: HandlerId, msg: AGN::Input) -> RequestId {
+        let request = RequestId {
+            handler: id,
+            serial: self.next_serial,
+        };
+        self.next_serial += 1;
+        self.scope.send(AgentUpdate::Request(msg, request));
+        request
+    }
+
+    /// Sends a message to the agent like a callback of `AgentLink::send_back` does.
+    pub fn send_message(&mut self, msg: AGN::Message) {
+        self.scope.send(AgentUpdate::Message(msg));
+    }
+
+    /// Takes outputs which the agent has sent to a handler.
+    pub fn take_outputs(&mut self, id: HandlerId) -> Vec<AGN::Output> {
+        let mut outputs = self.outputs.borrow_mut();
+        let (taken, rest) = mem::take(&mut *outputs)
+            .into_iter()
+            .partition(|&(handler, _)| handler == id);
+        *outputs = rest;
+        taken.into_iter().map(|(_, output)| output).collect()
+    }
+
+    /// Takes a response of the agent to a request.
+    pub fn take_response(&mut self, request: RequestId) -> Option<AGN::Output> {
+        let mut responses = self.responses.borrow_mut();
+        let index = responses.iter().position(|&(id, _)| id == request)?;
+        Some(responses.remove(index).1)
+    }
+}
+
+impl<AGN: Agent> Drop for AgentHarness<AGN> {
+    fn drop(&mut self) {
+        self.scope.send(AgentUpdate::Destroy);
+    }
+}
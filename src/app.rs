@@ -1,8 +1,14 @@
 //! This module contains `App` sctruct which used to bootstrap
 //! a component in an isolated scope.
 
-use stdweb::web::{document, Element, INode, IParentNode};
-use html::{Scope, Component, Renderable};
+use std::ops::{Deref, DerefMut};
+use stdweb::Value;
+use stdweb::web::{
+    document, Element, IElement, INode, IParentNode, Node, ShadowRoot, ShadowRootMode, TypedArray,
+};
+use failure::Error;
+use format::{Binary, Format, Text};
+use html::{Scope, Component, ComponentUpdate, Renderable};
 
 /// An application instance.
 pub struct App<COMP: Component> {
@@ -21,7 +27,7 @@ where
     }
 
     /// Alias to `mount("body", ...)`.
-    pub fn mount_to_body(self) -> Scope<COMP> {
+    pub fn mount_to_body(self) -> AppHandle<COMP> {
         // Bootstrap the component for `Window` environment only (not for `Worker`)
         let element = document()
             .query_selector("body")
@@ -34,16 +40,176 @@ where
     /// function in Elm. You should provide an initial model, `update` function
     /// which will update the state of the model and a `view` function which
     /// will render the model to a virtual DOM tree.
-    pub fn mount(self, element: Element) -> Scope<COMP> {
-        clear_element(&element);
-        self.scope.mount_in_place(element, None, None, None)
+    pub fn mount(self, element: Element) -> AppHandle<COMP> {
+        self.mount_to_node(element.as_node().to_owned(), None)
     }
+
+    /// Mounts the root component with initial properties instead of default ones.
+    pub fn mount_with_props(self, element: Element, props: COMP::Properties) -> AppHandle<COMP> {
+        self.mount_to_node(element.as_node().to_owned(), Some(props))
+    }
+
+    /// Mounts the app into a shadow root to isolate its styles from the page.
+    pub fn mount_to_shadow_root(self, root: ShadowRoot) -> AppHandle<COMP> {
+        self.mount_to_node(root.as_node().to_owned(), None)
+    }
+
+    /// Mounts the app into a shadow root of the host element.
+    /// An open shadow root is attached to the host if it doesn't have one.
+    pub fn mount_with_shadow(self, host: Element) -> AppHandle<COMP> {
+        let root = match host.shadow_root() {
+            Some(root) => root,
+            None => host
+                .attach_shadow(ShadowRootMode::Open)
+                .expect("can't attach shadow root to the element"),
+        };
+        self.mount_to_shadow_root(root)
+    }
+
+    fn mount_to_node(self, parent: Node, props: Option<COMP::Properties>) -> AppHandle<COMP> {
+        clear_node(&parent);
+        self.mount_after_children(parent, props)
+    }
+
+    /// Mounts the app after existing children of the node and keeps them.
+    pub(crate) fn mount_after_children(
+        self,
+        parent: Node,
+        props: Option<COMP::Properties>,
+    ) -> AppHandle<COMP> {
+        let scope = self.scope.mount_in_place(parent, None, None, props);
+        AppHandle { scope }
+    }
+}
+
+/// A handle of a mounted application. It dereferences to the `Scope`
+/// of the root component to send messages to it.
+pub struct AppHandle<COMP: Component> {
+    scope: Scope<COMP>,
 }
 
-/// Removes anything from the given element.
-fn clear_element(element: &Element) {
-    while let Some(child) = element.last_child() {
-        element.remove_child(&child).expect("can't remove a child");
+impl<COMP> AppHandle<COMP>
+where
+    COMP: Component + Renderable<COMP>,
+{
+    /// Destroys the components tree depth-first, removes its nodes
+    /// from the mount element and drops all listeners and tasks.
+    pub fn unmount(mut self) {
+        self.scope.destroy();
+    }
+
+    /// Sends new properties to the root component. It's re-rendered
+    /// if `change` method of the component returns `true`.
+    pub fn set_props(&mut self, props: COMP::Properties) {
+        self.scope.send(ComponentUpdate::Properties(props));
+    }
+
+    /// Creates a JS object with `send(data)` method which deserializes data
+    /// with a text format and sends it as a message to the root component.
+    /// Objects passed to `send` are converted to JSON strings. The method
+    /// throws an `Error` if data can't be deserialized. Call `dispose()`
+    /// to release the object.
+    ///
+    /// ```ignore
+    /// let bridge = App::<Model>::new().mount_to_body().js_bridge::<Json<_>>();
+    /// js! { window.app = @{bridge}; }
+    /// // In JavaScript: app.send({ "Payload": "text" });
+    /// ```
+    pub fn js_bridge<F>(&self) -> Value
+    where
+        F: From<Text> + 'static,
+        Format<COMP::Message>: From<F>,
+    {
+        let deliver = self.deliver::<String, F>();
+        let send = move |data: String| deliver(data);
+        js!(
+            var send = @{send};
+            return {
+                send: function(data) {
+                    if (typeof data !== "string") {
+                        data = JSON.stringify(data);
+                    }
+                    var error = send(data);
+                    if (error !== null) {
+                        throw new Error(error);
+                    }
+                },
+                dispose: function() {
+                    send.drop();
+                },
+            };
+        )
+    }
+
+    /// Creates a JS object like `js_bridge`, but `send(data)` takes a `Uint8Array`
+    /// (or an `ArrayBuffer`) and deserializes it with a binary format, like `Cbor` or `MsgPack`.
+    pub fn js_binary_bridge<F>(&self) -> Value
+    where
+        F: From<Binary> + 'static,
+        Format<COMP::Message>: From<F>,
+    {
+        let deliver = self.deliver::<Vec<u8>, F>();
+        let send = move |data: TypedArray<u8>| deliver(data.to_vec());
+        js!(
+            var send = @{send};
+            return {
+                send: function(data) {
+                    if (!(data instanceof Uint8Array)) {
+                        data = new Uint8Array(data);
+                    }
+                    var error = send(data);
+                    if (error !== null) {
+                        throw new Error(error);
+                    }
+                },
+                dispose: function() {
+                    send.drop();
+                },
+            };
+        )
+    }
+
+    /// Returns a function which deserializes data with a format and sends it
+    /// to the root component. It returns a description of an error if it fails.
+    fn deliver<T, F>(&self) -> impl Fn(T) -> Option<String>
+    where
+        F: From<Result<T, Error>>,
+        Format<COMP::Message>: From<F>,
+    {
+        let scope = self.scope.clone();
+        move |data: T| {
+            let format = F::from(Ok(data));
+            match Format::<COMP::Message>::from(format) {
+                Ok(msg) => {
+                    scope.clone().send_message(msg);
+                    None
+                }
+                Err(err) => {
+                    warn!("can't deserialize a message from JS: {}", err);
+                    Some(err.to_string())
+                }
+            }
+        }
+    }
+}
+
+impl<COMP: Component> Deref for AppHandle<COMP> {
+    type Target = Scope<COMP>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<COMP: Component> DerefMut for AppHandle<COMP> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
     }
 }
 
+/// Removes anything from the given element or shadow root.
+fn clear_node(parent: &Node) {
+    while let Some(child) = parent.last_child() {
+        parent.remove_child(&child).expect("can't remove a child");
+    }
+}
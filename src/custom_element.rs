@@ -0,0 +1,190 @@
+//! This module contains a registration of components as
+//! [custom elements](https://developer.mozilla.org/en-US/docs/Web/Web_Components/Using_custom_elements)
+//! to use them from a plain HTML.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use serde::Serialize;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use stdweb::web::{Element, IElement, INode};
+use app::{App, AppHandle};
+use callback::Callback;
+use html::{Component, Renderable};
+
+/// A component which could be used as a custom element.
+pub trait CustomElement: Component + Renderable<Self> {
+    /// Names of attributes which changes are passed to the component.
+    fn observed_attributes() -> &'static [&'static str];
+
+    /// Sets a value of an observed attribute to properties.
+    /// The value is `None` if the attribute was removed.
+    fn set_attribute(props: &mut Self::Properties, name: &str, value: Option<String>);
+
+    /// Sets output callbacks of properties which dispatch DOM events.
+    fn connect_events(_props: &mut Self::Properties, _dispatcher: &EventDispatcher) {}
+}
+
+/// Dispatches `CustomEvent`s from a host element of a custom element.
+pub struct EventDispatcher {
+    element: Element,
+}
+
+impl EventDispatcher {
+    /// Dispatches a bubbling `CustomEvent` with serialized data in `detail` field.
+    pub fn dispatch<T: Serialize>(&self, event: &str, detail: &T) {
+        let detail = ::serde_json::to_string(detail).expect("can't serialize event detail");
+        js! { @(no_return)
+            var element = @{&self.element};
+            var event = new CustomEvent(@{event}, {
+                detail: JSON.parse(@{detail}),
+                bubbles: true,
+                composed: true,
+            });
+            element.dispatchEvent(event);
+        }
+    }
+
+    /// Creates a callback which dispatches an event with the name.
+    pub fn callback<T: Serialize + 'static>(&self, event: &'static str) -> Callback<T> {
+        let dispatcher = EventDispatcher {
+            element: self.element.clone(),
+        };
+        Callback::from(move |detail: T| dispatcher.dispatch(event, &detail))
+    }
+}
+
+/// A state of a custom element.
+struct Instance<COMP: CustomElement> {
+    props: COMP::Properties,
+    handle: Option<AppHandle<COMP>>,
+}
+
+type Instances<COMP> = Rc<RefCell<HashMap<u32, Instance<COMP>>>>;
+
+/// Registers a component as a custom element with the name (it must contain a hyphen).
+/// The component is mounted into the element when it's connected to the document
+/// and destroyed when the element is disconnected. Children of the element which
+/// were set by the page are kept, the component is rendered after them.
+pub fn define<COMP: CustomElement>(name: &str) {
+    let instances: Instances<COMP> = Rc::new(RefCell::new(HashMap::new()));
+    let counter = Rc::new(Cell::new(0));
+
+    let connect = {
+        let instances = instances.clone();
+        let counter = counter.clone();
+        move |element: Value| {
+            let element: Element = element.try_into().expect("custom element expected");
+            let id = instance_id(&element, &counter);
+            // The borrow is released before mounting, because the component
+            // could render nested elements of the same type synchronously
+            let props = {
+                let mut instances = instances.borrow_mut();
+                let instance = instances
+                    .entry(id)
+                    .or_insert_with(|| Instance::new(&element));
+                if instance.handle.is_some() {
+                    return;
+                }
+                instance.props.clone()
+            };
+            let node = element.as_node().to_owned();
+            let handle = App::<COMP>::new().mount_after_children(node, Some(props));
+            if let Some(instance) = instances.borrow_mut().get_mut(&id) {
+                instance.handle = Some(handle);
+                return;
+            }
+            // The element was disconnected during mounting
+            handle.unmount();
+        }
+    };
+
+    let disconnect = {
+        let instances = instances.clone();
+        let counter = counter.clone();
+        move |element: Value| {
+            let element: Element = element.try_into().expect("custom element expected");
+            let id = instance_id(&element, &counter);
+            let instance = instances.borrow_mut().remove(&id);
+            js! { @(no_return) delete @{&element}.__yew_id; }
+            // Unmounting locks the scheduler, so messages which are sent
+            // during the destroying are processed after it
+            if let Some(handle) = instance.and_then(|instance| instance.handle) {
+                handle.unmount();
+            }
+        }
+    };
+
+    let changed = move |element: Value, name: String, value: Value| {
+        let element: Element = element.try_into().expect("custom element expected");
+        let id = instance_id(&element, &counter);
+        // Attributes of a disconnected element are read when it's connected
+        let update = {
+            let mut instances = instances.borrow_mut();
+            instances.get_mut(&id).map(|instance| {
+                COMP::set_attribute(&mut instance.props, &name, value.into_string());
+                (instance.props.clone(), instance.handle.take())
+            })
+        };
+        if let Some((props, Some(mut handle))) = update {
+            handle.set_props(props);
+            if let Some(instance) = instances.borrow_mut().get_mut(&id) {
+                instance.handle = Some(handle);
+                return;
+            }
+            handle.unmount();
+        }
+    };
+
+    let attributes: Vec<&str> = COMP::observed_attributes().to_vec();
+    js! { @(no_return)
+        var connect = @{connect};
+        var disconnect = @{disconnect};
+        var changed = @{changed};
+        var attributes = @{attributes};
+        class YewElement extends HTMLElement {
+            static get observedAttributes() {
+                return attributes;
+            }
+            connectedCallback() {
+                connect(this);
+            }
+            disconnectedCallback() {
+                disconnect(this);
+            }
+            attributeChangedCallback(name, oldValue, newValue) {
+                changed(this, name, newValue);
+            }
+        }
+        customElements.define(@{name}, YewElement);
+    }
+}
+
+impl<COMP: CustomElement> Instance<COMP> {
+    fn new(element: &Element) -> Self {
+        let mut props = COMP::Properties::default();
+        let dispatcher = EventDispatcher {
+            element: element.clone(),
+        };
+        COMP::connect_events(&mut props, &dispatcher);
+        for name in COMP::observed_attributes() {
+            COMP::set_attribute(&mut props, name, element.get_attribute(name));
+        }
+        Instance {
+            props,
+            handle: None,
+        }
+    }
+}
+
+/// Returns an id of the instance which is kept in a property of the element.
+fn instance_id(element: &Element, counter: &Cell<u32>) -> u32 {
+    let id: Option<u32> = js!( return @{element}.__yew_id; ).try_into().ok();
+    id.unwrap_or_else(|| {
+        let id = counter.get() + 1;
+        counter.set(id);
+        js! { @(no_return) @{element}.__yew_id = @{id}; }
+        id
+    })
+}
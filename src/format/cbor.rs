@@ -15,3 +15,5 @@ use serde_cbor;
 pub struct Cbor<T>(pub T);
 
 binary_format!(Cbor based on serde_cbor);
+
+unwrap_format!(Cbor);
@@ -17,3 +17,5 @@ pub struct Json<T>(pub T);
 text_format!(Json based on serde_json);
 
 binary_format!(Json based on serde_json);
+
+unwrap_format!(Json);
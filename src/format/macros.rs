@@ -49,3 +49,13 @@ macro_rules! binary_format {
         }
     };
 }
+
+macro_rules! unwrap_format {
+    ($type:ident) => {
+        impl<T> From<$type<$crate::format::Format<T>>> for $crate::format::Format<T> {
+            fn from(value: $type<$crate::format::Format<T>>) -> Self {
+                value.0
+            }
+        }
+    };
+}
@@ -15,3 +15,5 @@ use rmp_serde;
 pub struct MsgPack<T>(pub T);
 
 binary_format!(MsgPack based on rmp_serde);
+
+unwrap_format!(MsgPack);
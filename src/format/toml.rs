@@ -17,3 +17,5 @@ pub struct Toml<T>(pub T);
 text_format!(Toml based on toml);
 
 binary_format!(Toml based on toml);
+
+unwrap_format!(Toml);
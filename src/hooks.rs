@@ -0,0 +1,357 @@
+//! This module contains hooks: small units of a stateful logic which could be
+//! attached to any component through its `ComponentLink`.
+//!
+//! Hooks are stored in the `Scope` of a component. Changes of a state
+//! schedule a re-rendering of the component, effects are called after
+//! a render pass and cleaned up when dependencies change or when
+//! the component is destroyed.
+//!
+//! Hooks have to be registered in `Component::create` and kept in fields of
+//! the component. Registering a hook later (in `update` or `view`) panics,
+//! because every call adds a new hook to the scope.
+
+use std::any::Any;
+use std::cell::{Cell, Ref, RefCell};
+use std::mem;
+use std::rc::Rc;
+use callback::Callback;
+use html::ShouldRender;
+use scheduler::Shared;
+
+/// A routine which is called to release resources of an effect.
+pub type Cleanup = Option<Box<dyn FnOnce()>>;
+
+/// A function which schedules a re-rendering of a component.
+pub(crate) type Render = Rc<dyn Fn()>;
+
+/// Hooks registered in a scope of a component.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    states: Vec<Rc<dyn Any>>,
+    effects: Vec<Box<dyn Effect>>,
+    locked: bool,
+}
+
+impl Hooks {
+    fn add_state(&mut self, state: Rc<dyn Any>) {
+        self.check_unlocked();
+        self.states.push(state);
+    }
+
+    fn add_effect(&mut self, effect: Box<dyn Effect>) {
+        self.check_unlocked();
+        self.effects.push(effect);
+    }
+
+    fn check_unlocked(&self) {
+        if self.locked {
+            panic!("hooks have to be registered in `Component::create` only");
+        }
+    }
+}
+
+/// Forbids registering of new hooks. It's called when the component was created.
+pub(crate) fn lock(hooks: &Shared<Hooks>) {
+    hooks.borrow_mut().locked = true;
+}
+
+/// Calls effects which dependencies had changed. Effects are taken out
+/// of the hooks for the call, because an effect could change a state
+/// or destroy the component.
+pub(crate) fn run_effects(hooks: &Shared<Hooks>) {
+    let mut effects = mem::take(&mut hooks.borrow_mut().effects);
+    for effect in &mut effects {
+        effect.run();
+    }
+    let mut hooks = hooks.borrow_mut();
+    effects.append(&mut hooks.effects);
+    hooks.effects = effects;
+}
+
+/// Cleans up all effects and drops all states of a destroyed component.
+pub(crate) fn destroy(hooks: &Shared<Hooks>) {
+    let (effects, _states) = {
+        let mut hooks = hooks.borrow_mut();
+        (
+            mem::take(&mut hooks.effects),
+            mem::take(&mut hooks.states),
+        )
+    };
+    for mut effect in effects {
+        effect.cleanup();
+    }
+}
+
+/// A local state of a component. Every change of the value
+/// schedules a re-rendering of the component.
+pub struct StateHandle<T> {
+    value: Rc<RefCell<T>>,
+    render: Render,
+}
+
+impl<T: 'static> StateHandle<T> {
+    pub(crate) fn new(hooks: &Shared<Hooks>, initial: T, render: Render) -> Self {
+        let value = Rc::new(RefCell::new(initial));
+        hooks.borrow_mut().add_state(value.clone());
+        StateHandle { value, render }
+    }
+
+    /// Borrows the current value.
+    pub fn get(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Replaces the value and schedules a re-rendering.
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+        (self.render)();
+    }
+
+    /// Changes the value in place and schedules a re-rendering.
+    pub fn update<F: FnOnce(&mut T)>(&self, function: F) {
+        function(&mut self.value.borrow_mut());
+        (self.render)();
+    }
+
+    /// Creates a callback which sets the value.
+    pub fn setter(&self) -> Callback<T> {
+        let this = self.clone();
+        Callback::from(move |value| this.set(value))
+    }
+}
+
+impl<T> Clone for StateHandle<T> {
+    fn clone(&self) -> Self {
+        StateHandle {
+            value: self.value.clone(),
+            render: self.render.clone(),
+        }
+    }
+}
+
+/// A state which is changed by actions of its own type.
+pub trait Reducer: 'static {
+    /// Type of actions which the state is reduced with.
+    type Action;
+
+    /// Changes the state and returns `true` if the component should be re-rendered.
+    fn reduce(&mut self, action: Self::Action) -> ShouldRender;
+}
+
+/// A handle to a state which implements `Reducer`.
+pub struct ReducerHandle<R: Reducer> {
+    state: StateHandle<R>,
+}
+
+impl<R: Reducer> ReducerHandle<R> {
+    pub(crate) fn new(hooks: &Shared<Hooks>, initial: R, render: Render) -> Self {
+        ReducerHandle {
+            state: StateHandle::new(hooks, initial, render),
+        }
+    }
+
+    /// Borrows the current state.
+    pub fn get(&self) -> Ref<'_, R> {
+        self.state.get()
+    }
+
+    /// Reduces the state with an action and schedules a re-rendering if it's necessary.
+    pub fn dispatch(&self, action: R::Action) {
+        let should_render = self.state.value.borrow_mut().reduce(action);
+        if should_render {
+            (self.state.render)();
+        }
+    }
+
+    /// Creates a callback which dispatches actions.
+    pub fn dispatcher(&self) -> Callback<R::Action> {
+        let this = self.clone();
+        Callback::from(move |action| this.dispatch(action))
+    }
+}
+
+impl<R: Reducer> Clone for ReducerHandle<R> {
+    fn clone(&self) -> Self {
+        ReducerHandle {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// A type-erased effect.
+trait Effect {
+    /// Calls the effect if dependencies had changed.
+    fn run(&mut self);
+    /// Calls a cleanup of the last call.
+    fn cleanup(&mut self);
+}
+
+struct EffectHook<D, G, F> {
+    deps: G,
+    effect: F,
+    last: Option<D>,
+    cleanup: Cleanup,
+}
+
+impl<D, G, F> Effect for EffectHook<D, G, F>
+where
+    D: PartialEq,
+    G: Fn() -> D,
+    F: Fn(&D) -> Cleanup,
+{
+    fn run(&mut self) {
+        let deps = (self.deps)();
+        if self.last.as_ref() != Some(&deps) {
+            self.cleanup();
+            self.cleanup = (self.effect)(&deps);
+            self.last = Some(deps);
+        }
+    }
+
+    fn cleanup(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+pub(crate) fn add_effect<D, G, F>(hooks: &Shared<Hooks>, deps: G, effect: F)
+where
+    D: PartialEq + 'static,
+    G: Fn() -> D + 'static,
+    F: Fn(&D) -> Cleanup + 'static,
+{
+    let hook = EffectHook {
+        deps,
+        effect,
+        last: None,
+        cleanup: None,
+    };
+    hooks.borrow_mut().add_effect(Box::new(hook));
+}
+
+/// A value which is recomputed only when its dependencies change.
+pub struct MemoHandle<D, T> {
+    deps: Rc<dyn Fn() -> D>,
+    compute: Rc<dyn Fn(&D) -> T>,
+    cache: Rc<RefCell<Option<(D, T)>>>,
+}
+
+impl<D: PartialEq + 'static, T: 'static> MemoHandle<D, T> {
+    pub(crate) fn new<G, F>(hooks: &Shared<Hooks>, deps: G, compute: F) -> Self
+    where
+        G: Fn() -> D + 'static,
+        F: Fn(&D) -> T + 'static,
+    {
+        let cache = Rc::new(RefCell::new(None));
+        hooks.borrow_mut().add_state(cache.clone());
+        MemoHandle {
+            deps: Rc::new(deps),
+            compute: Rc::new(compute),
+            cache,
+        }
+    }
+
+    /// Returns the value and recomputes it if dependencies had changed.
+    pub fn get(&self) -> Ref<'_, T> {
+        let deps = (self.deps)();
+        let outdated = match *self.cache.borrow() {
+            Some((ref last, _)) => *last != deps,
+            None => true,
+        };
+        if outdated {
+            let value = (self.compute)(&deps);
+            *self.cache.borrow_mut() = Some((deps, value));
+        }
+        Ref::map(self.cache.borrow(), |cache| {
+            &cache.as_ref().expect("memo value is not computed").1
+        })
+    }
+}
+
+impl<D, T> Clone for MemoHandle<D, T> {
+    fn clone(&self) -> Self {
+        MemoHandle {
+            deps: self.deps.clone(),
+            compute: self.compute.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// Keeps hooks without a component and counts scheduled re-renderings.
+/// It allows to test a stateful logic natively with `cargo test`.
+pub struct HookHarness {
+    hooks: Shared<Hooks>,
+    renders: Rc<Cell<usize>>,
+}
+
+impl Default for HookHarness {
+    fn default() -> Self {
+        HookHarness::new()
+    }
+}
+
+impl HookHarness {
+    /// Creates an empty set of hooks.
+    pub fn new() -> Self {
+        HookHarness {
+            hooks: Rc::new(RefCell::new(Hooks::default())),
+            renders: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Registers a local state like `ComponentLink::use_state`.
+    pub fn use_state<T: 'static>(&mut self, initial: T) -> StateHandle<T> {
+        StateHandle::new(&self.hooks, initial, self.render_counter())
+    }
+
+    /// Registers a state like `ComponentLink::use_reducer`.
+    pub fn use_reducer<R: Reducer>(&mut self, initial: R) -> ReducerHandle<R> {
+        ReducerHandle::new(&self.hooks, initial, self.render_counter())
+    }
+
+    /// Registers an effect like `ComponentLink::use_effect`.
+    pub fn use_effect<D, G, F>(&mut self, deps: G, effect: F)
+    where
+        D: PartialEq + 'static,
+        G: Fn() -> D + 'static,
+        F: Fn(&D) -> Cleanup + 'static,
+    {
+        add_effect(&self.hooks, deps, effect);
+    }
+
+    /// Registers a value like `ComponentLink::use_memo`.
+    pub fn use_memo<D, T, G, F>(&mut self, deps: G, compute: F) -> MemoHandle<D, T>
+    where
+        D: PartialEq + 'static,
+        T: 'static,
+        G: Fn() -> D + 'static,
+        F: Fn(&D) -> T + 'static,
+    {
+        MemoHandle::new(&self.hooks, deps, compute)
+    }
+
+    /// Finishes registering of hooks like after `Component::create`
+    /// and calls effects like after a render pass.
+    pub fn render(&mut self) {
+        lock(&self.hooks);
+        run_effects(&self.hooks);
+    }
+
+    /// Returns the count of re-renderings scheduled by states.
+    pub fn renders(&self) -> usize {
+        self.renders.get()
+    }
+
+    fn render_counter(&self) -> Render {
+        let renders = self.renders.clone();
+        Rc::new(move || renders.set(renders.get() + 1))
+    }
+}
+
+impl Drop for HookHarness {
+    fn drop(&mut self) {
+        destroy(&self.hooks);
+    }
+}
@@ -5,11 +5,13 @@
 
 use std::rc::Rc;
 use std::cell::RefCell;
-use stdweb::web::{Element, EventListenerHandle, FileList, INode, Node};
+use stdweb::web::{EventListenerHandle, FileList, INode, Node};
 use stdweb::web::html_element::SelectElement;
-use virtual_dom::{Listener, VDiff, VNode};
+use virtual_dom::{Listener, NodeState, VDiff, VNode};
 use callback::Callback;
+use hooks::{self, Cleanup, Hooks, MemoHandle, Reducer, ReducerHandle, Render, StateHandle};
 use scheduler::{Runnable, Shared, scheduler};
+use services::{Task, TaskKey, TaskRegistry};
 
 /// This type indicates that component should be rendered again.
 pub type ShouldRender = bool;
@@ -50,6 +52,8 @@ pub(crate) enum ComponentUpdate< COMP: Component> {
     Message(COMP::Message),
     /// Wraps properties for a component.
     Properties(COMP::Properties),
+    /// Renders the component again, because a state of a hook had changed
+    Render,
     /// Removes the component
     Destroy,
 }
@@ -87,18 +91,86 @@ where
     pub fn send_self(&mut self, msg: COMP::Message) {
         self.scope.send_message(msg);
     }
+
+    /// Registers a local state. Every change of the state re-renders the component.
+    /// Hooks have to be registered in `Component::create` only, it panics otherwise.
+    pub fn use_state<T: 'static>(&mut self, initial: T) -> StateHandle<T> {
+        StateHandle::new(&self.scope.hooks, initial, self.render())
+    }
+
+    /// Registers a state which is changed by actions of its own type.
+    pub fn use_reducer<R: Reducer>(&mut self, initial: R) -> ReducerHandle<R> {
+        ReducerHandle::new(&self.scope.hooks, initial, self.render())
+    }
+
+    /// Registers an effect which is called after a render pass every time when
+    /// the value returned by `deps` changes. The cleanup returned by the effect
+    /// is called before the next call and when the component is destroyed.
+    pub fn use_effect<D, G, F>(&mut self, deps: G, effect: F)
+    where
+        D: PartialEq + 'static,
+        G: Fn() -> D + 'static,
+        F: Fn(&D) -> Cleanup + 'static,
+    {
+        hooks::add_effect(&self.scope.hooks, deps, effect);
+    }
+
+    /// Registers a value which is recomputed only when the value returned by `deps` changes.
+    pub fn use_memo<D, T, G, F>(&mut self, deps: G, compute: F) -> MemoHandle<D, T>
+    where
+        D: PartialEq + 'static,
+        T: 'static,
+        G: Fn() -> D + 'static,
+        F: Fn(&D) -> T + 'static,
+    {
+        MemoHandle::new(&self.scope.hooks, deps, compute)
+    }
+
+    /// Keeps a task of a service (`FetchTask`, `TimeoutTask`, etc.) with a key
+    /// till the component is destroyed. A previous task with the same key is canceled.
+    pub fn register_task<K, T>(&mut self, key: K, task: T)
+    where
+        K: Into<TaskKey>,
+        T: Task + 'static,
+    {
+        self.scope.tasks.borrow_mut().insert(key.into(), Box::new(task));
+    }
+
+    /// Keeps a task of a service till the component is destroyed.
+    pub fn add_task<T: Task + 'static>(&mut self, task: T) {
+        self.scope.tasks.borrow_mut().push(Box::new(task));
+    }
+
+    /// Cancels a task with the key. Returns `false` if there was no such task.
+    pub fn cancel_task(&mut self, key: &str) -> bool {
+        self.scope.tasks.borrow_mut().cancel(key)
+    }
+
+    /// Checks that a task with the key is registered and active.
+    pub fn is_task_active(&self, key: &str) -> bool {
+        self.scope.tasks.borrow().is_active(key)
+    }
+
+    fn render(&self) -> Render {
+        let scope = self.scope.clone();
+        Rc::new(move || scope.clone().send(ComponentUpdate::Render))
+    }
 }
 
 /// A context which contains a bridge to send a messages to a loop.
 /// Mostly services uses it.
 pub struct Scope<COMP: Component> {
     shared_component: Shared<Option<ComponentRunnable<COMP>>>,
+    hooks: Shared<Hooks>,
+    tasks: Shared<TaskRegistry>,
 }
 
 impl<COMP: Component> Clone for Scope<COMP> {
     fn clone(&self) -> Self {
         Scope {
             shared_component: self.shared_component.clone(),
+            hooks: self.hooks.clone(),
+            tasks: self.tasks.clone(),
         }
     }
 }
@@ -122,6 +194,23 @@ where
         let update = ComponentUpdate::Message(message);
         self.send(update);
     }
+
+    /// Destroys the component with its children immediately, or schedules
+    /// the destroying if the component is busy with an update now.
+    /// The scheduler is locked while the component is destroyed, so messages
+    /// which are sent by `destroy` methods or cleanups are processed later.
+    pub(crate) fn destroy(&mut self) {
+        let mut scope = self.clone();
+        scheduler().run_locked(move || {
+            if let Ok(mut component) = scope.shared_component.try_borrow_mut() {
+                if let Some(this) = component.as_mut() {
+                    this.destroy();
+                }
+                return;
+            }
+            scope.send(ComponentUpdate::Destroy);
+        });
+    }
 }
 
 /// Holder for the element.
@@ -133,14 +222,16 @@ where
 {
     pub(crate) fn new() -> Self {
         let shared_component = Rc::new(RefCell::new(None));
-        Scope { shared_component }
+        let hooks = Rc::new(RefCell::new(Hooks::default()));
+        let tasks = Rc::new(RefCell::new(TaskRegistry::default()));
+        Scope { shared_component, hooks, tasks }
     }
 
-    // TODO Consider to use &Node instead of Element as parent
-    /// Mounts elements in place of previous node (ancestor).
+    /// Mounts elements in place of previous node (ancestor). The parent
+    /// could be an `Element` or a `ShadowRoot`.
     pub(crate) fn mount_in_place(
         self,
-        element: Element,
+        parent: Node,
         ancestor: Option<VNode<COMP>>,
         occupied: Option<NodeCell>,
         init_props: Option<COMP::Properties>,
@@ -149,7 +240,7 @@ where
             env: self.clone(),
             component: None,
             last_frame: None,
-            element,
+            parent,
             ancestor,
             occupied,
             init_props,
@@ -167,13 +258,42 @@ struct ComponentRunnable<COMP: Component> {
     env: Scope<COMP>,
     component: Option<COMP>,
     last_frame: Option<VNode<COMP>>,
-    element: Element,
+    parent: Node,
     ancestor: Option<VNode<COMP>>,
     occupied: Option<NodeCell>,
     init_props: Option<COMP::Properties>,
     destroyed: bool,
 }
 
+impl<COMP> ComponentRunnable<COMP>
+where
+    COMP: Component + Renderable<COMP>,
+{
+    /// Detaches the rendered nodes (it destroys child components depth-first),
+    /// destroys the component and releases its hooks and tasks.
+    fn destroy(&mut self) {
+        if self.destroyed {
+            return;
+        }
+        self.destroyed = true;
+        let parent = &self.parent;
+        if let Some(mut frame) = self.last_frame.take() {
+            frame.detach(parent);
+        } else if let Some(mut ancestor) = self.ancestor.take() {
+            // The component was not created yet, remove a placeholder
+            ancestor.detach(parent);
+        }
+        if let Some(ref mut cell) = self.occupied {
+            cell.borrow_mut().take();
+        }
+        if let Some(mut component) = self.component.take() {
+            component.destroy();
+        }
+        hooks::destroy(&self.env.hooks);
+        self.env.tasks.borrow_mut().cancel_all();
+    }
+}
+
 /// Wraps a component reference and a message to hide it under `Runnable` trait.
 /// It's necessary to schedule a processing of a message.
 struct ComponentEnvelope<COMP>
@@ -205,16 +325,18 @@ where
             ComponentUpdate::Create(link) => {
                 let props = this.init_props.take().unwrap_or_default();
                 this.component = Some(COMP::create(props, link));
+                hooks::lock(&env.hooks);
                 // No messages at start
                 let current_frame = this.component.as_ref().unwrap().view();
                 this.last_frame = Some(current_frame);
                 // First-time rendering the tree
                 let node = this.last_frame.as_mut()
                     .unwrap()
-                    .apply(this.element.as_node(), None, this.ancestor.take(), &env);
+                    .apply(&this.parent, None, this.ancestor.take(), &env);
                 if let Some(ref mut cell) = this.occupied {
                     *cell.borrow_mut() = node;
                 }
+                hooks::run_effects(&env.hooks);
             }
             ComponentUpdate::Message(msg) => {
                 should_update |= this.component.as_mut()
@@ -226,21 +348,29 @@ where
                     .expect("component was not created to process properties")
                     .change(props);
             }
+            ComponentUpdate::Render => {
+                should_update = this.component.is_some();
+            }
             ComponentUpdate::Destroy => {
-                // TODO this.component.take() instead of destroyed
-                this.component.as_mut().unwrap().destroy();
-                this.destroyed = true;
+                this.destroy();
             }
         }
         if should_update {
             let mut next_frame = this.component.as_ref().unwrap().view();
+            // Keep the focus and the caret of the active element, because
+            // it could be moved or rewritten during the render
+            let active = NodeState::active();
             // Re-rendering the tree
             let node =
-                next_frame.apply(this.element.as_node(), None, this.last_frame.take(), &env);
+                next_frame.apply(&this.parent, None, this.last_frame.take(), &env);
+            if let Some(state) = active {
+                state.restore();
+            }
             if let Some(ref mut cell) = this.occupied {
                 *cell.borrow_mut() = node;
             }
             this.last_frame = Some(next_frame);
+            hooks::run_effects(&env.hooks);
         }
     }
 }
@@ -423,28 +553,147 @@ pub enum ChangeData {
     Files(FileList),
 }
 
+/// Schemes which are allowed for URLs by default.
+const DEFAULT_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ftp"];
+
+/// Schemes which are allowed for `src` attributes by default. Inline and
+/// local resources (from `URL.createObjectURL`) are allowed for media.
+const DEFAULT_SOURCE_SCHEMES: &[&str] = &["http", "https", "data", "blob"];
+
+/// A placeholder which replaces a URL with a forbidden scheme.
+const BLOCKED_URL: &str = "about:invalid";
+
+thread_local! {
+    static ALLOWED_SCHEMES: RefCell<Vec<String>> = RefCell::new(
+        DEFAULT_SCHEMES.iter().map(|scheme| scheme.to_string()).collect()
+    );
+    static ALLOWED_SOURCE_SCHEMES: RefCell<Vec<String>> = RefCell::new(
+        DEFAULT_SOURCE_SCHEMES.iter().map(|scheme| scheme.to_string()).collect()
+    );
+}
+
 /// A bridging type for checking `href` attribute value.
+/// It also used by `src`, `action` and `formaction` attributes of `html!` macro.
+///
+/// Conversion from a string normalizes a URL. Its scheme is checked against an
+/// allow-list (see `Href::set_allowed_schemes`) when it's converted to a string, and
+/// against the list of `Href::set_allowed_source_schemes` when it's used for `src`.
+/// Relative URLs are always allowed. A URL with a forbidden scheme (like `javascript:`)
+/// is replaced with `about:invalid`.
 #[derive(Debug)]
 pub struct Href {
     link: String,
+    trusted: bool,
+}
+
+impl Href {
+    /// Creates `Href` from a trusted link without any checks.
+    /// Use it carefully and never for user-provided data.
+    pub fn trusted<T: Into<String>>(link: T) -> Self {
+        Href {
+            link: link.into(),
+            trusted: true,
+        }
+    }
+
+    /// Sets schemes (like `https` or `mailto`) which are allowed for URLs
+    /// in the current thread. Default: `http`, `https`, `mailto`, `tel`, `ftp`.
+    pub fn set_allowed_schemes(schemes: &[&str]) {
+        ALLOWED_SCHEMES.with(|allowed| *allowed.borrow_mut() = lowercase(schemes));
+    }
+
+    /// Sets schemes which are allowed for `src` attributes in the current thread.
+    /// Default: `http`, `https`, `data`, `blob`.
+    pub fn set_allowed_source_schemes(schemes: &[&str]) {
+        ALLOWED_SOURCE_SCHEMES.with(|allowed| *allowed.borrow_mut() = lowercase(schemes));
+    }
+
+    /// Checks that a URL is relative or has an allowed scheme.
+    pub fn is_allowed(link: &str) -> bool {
+        match scheme_of(&normalize(link)) {
+            Some(scheme) => ALLOWED_SCHEMES.with(|allowed| allowed.borrow().contains(&scheme)),
+            None => true,
+        }
+    }
+
+    /// Checks that a URL is relative or has a scheme which is allowed for `src`.
+    pub fn is_allowed_source(link: &str) -> bool {
+        match scheme_of(&normalize(link)) {
+            Some(scheme) => {
+                ALLOWED_SOURCE_SCHEMES.with(|allowed| allowed.borrow().contains(&scheme))
+            }
+            None => true,
+        }
+    }
+
+    /// Returns the URL for a `src` attribute or `about:invalid`
+    /// if its scheme is not allowed for sources.
+    pub fn to_source_string(&self) -> String {
+        self.checked(Href::is_allowed_source(&self.link))
+    }
+
+    fn checked(&self, allowed: bool) -> String {
+        if self.trusted || allowed {
+            self.link.to_owned()
+        } else {
+            warn!("URL with a forbidden scheme was blocked: {}", self.link);
+            BLOCKED_URL.to_owned()
+        }
+    }
+}
+
+fn lowercase(schemes: &[&str]) -> Vec<String> {
+    schemes.iter().map(|scheme| scheme.to_ascii_lowercase()).collect()
+}
+
+/// Removes leading and trailing whitespaces and control chars, and
+/// tabs and newlines inside of a URL the same way as browsers do.
+fn normalize(link: &str) -> String {
+    link.trim_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| *c != '\t' && *c != '\n' && *c != '\r')
+        .collect()
+}
+
+/// Returns a lowercased scheme of an absolute URL.
+fn scheme_of(link: &str) -> Option<String> {
+    let end = link.find(':')?;
+    let scheme = &link[..end];
+    let mut chars = scheme.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {}
+        _ => return None,
+    }
+    if chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.') {
+        Some(scheme.to_ascii_lowercase())
+    } else {
+        None
+    }
 }
 
 impl From<String> for Href {
     fn from(link: String) -> Self {
-        Href { link }
+        Href::from(link.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Href {
+    fn from(link: &'a String) -> Self {
+        Href::from(link.as_str())
     }
 }
 
 impl<'a> From<&'a str> for Href {
     fn from(link: &'a str) -> Self {
         Href {
-            link: link.to_owned(),
+            link: normalize(link),
+            trusted: false,
         }
     }
 }
 
 impl ToString for Href {
     fn to_string(&self) -> String {
-        self.link.to_owned()
+        self.checked(Href::is_allowed(&self.link))
     }
 }
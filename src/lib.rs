@@ -93,6 +93,8 @@ pub mod callback;
 pub mod scheduler;
 pub mod agent;
 pub mod components;
+pub mod custom_element;
+pub mod hooks;
 
 /// Initializes yew framework. It should be called first.
 pub fn initialize() {
@@ -182,13 +184,15 @@ pub mod prelude {
         ShouldRender,
     };
 
-    pub use app::App;
+    pub use app::{App, AppHandle};
 
     pub use callback::Callback;
 
     pub use agent::{
         Bridge,
         Bridged,
+        Dispatched,
+        Dispatcher,
         Threaded,
     };
 
@@ -198,15 +202,27 @@ pub mod prelude {
     pub mod worker {
         pub use agent::{
             Agent,
+            AgentError,
+            AgentErrorKind,
             AgentLink,
             Bridge,
             Bridged,
+            Codec,
             Context,
+            Dispatched,
+            Dispatcher,
             Global,
             HandlerId,
             Job,
+            Pool,
             Private,
             Public,
+            RequestError,
+            RequestId,
+            RequestTask,
+            ResponseCallback,
+            RestartPolicy,
+            TransferBuffer,
             Transferable,
         };
     }
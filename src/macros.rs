@@ -1,8 +1,8 @@
 //! This module contains macros which implements `html!` macro
 //! and JSX-like templates.
 
-use html::Component;
-use virtual_dom::{Listener, VNode};
+use html::{Component, Href};
+use virtual_dom::{Classes, Listener, VNode, VTag};
 
 #[doc(hidden)]
 #[macro_export]
@@ -33,6 +33,17 @@ macro_rules! html_impl {
         $crate::macros::child_to_parent(&mut $stack, None);
         html_impl! { $stack ($($tail)*) }
     };
+    // Start of opening tag with a dynamic name: <@{tag_name}>
+    ($stack:ident (< @ { $tag:expr } $($tail:tt)*)) => {
+        let vtag = $crate::virtual_dom::VTag::new($tag);
+        $stack.push(vtag.into());
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    // Closing of a tag with a dynamic name: </@>
+    ($stack:ident (< / @ > $($tail:tt)*)) => {
+        $crate::macros::child_to_parent(&mut $stack, None);
+        html_impl! { $stack ($($tail)*) }
+    };
     // Start of component tag
     ($stack:ident (< $comp:ty : $($tail:tt)*)) => {
         #[allow(unused_mut)]
@@ -72,10 +83,17 @@ macro_rules! html_impl {
         $( $crate::macros::append_class(&mut $stack, $class); )*
         html_impl! { @vtag $stack ($($tail)*) }
     };
+    // PATTERN: class=classes!("class-1", ("active", flag)),
+    // or any other value which implements `Into<Classes>`
     (@vtag $stack:ident (class = $class:expr, $($tail:tt)*)) => {
         $crate::macros::set_classes(&mut $stack, $class);
         html_impl! { @vtag $stack ($($tail)*) }
     };
+    // PATTERN: ..attributes, - spreads a prebuilt map of attributes
+    (@vtag $stack:ident (.. $attrs:expr, $($tail:tt)*)) => {
+        $crate::macros::spread_attributes(&mut $stack, $attrs);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
     // PATTERN: value="",
     (@vtag $stack:ident (value = $value:expr, $($tail:tt)*)) => {
         $crate::macros::set_value_or_attribute(&mut $stack, $value);
@@ -229,11 +247,27 @@ macro_rules! html_impl {
         html_impl! { @vtag $stack ($($tail)*) }
     };
     // Attributes:
+    // URL attributes are checked with `Href`
     (@vtag $stack:ident (href = $href:expr, $($tail:tt)*)) => {
         let href: $crate::html::Href = $href.into();
         $crate::macros::add_attribute(&mut $stack, "href", href);
         html_impl! { @vtag $stack ($($tail)*) }
     };
+    (@vtag $stack:ident (src = $src:expr, $($tail:tt)*)) => {
+        let src: $crate::html::Href = $src.into();
+        $crate::macros::add_attribute(&mut $stack, "src", src.to_source_string());
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    (@vtag $stack:ident (action = $action:expr, $($tail:tt)*)) => {
+        let action: $crate::html::Href = $action.into();
+        $crate::macros::add_attribute(&mut $stack, "action", action);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    (@vtag $stack:ident (formaction = $action:expr, $($tail:tt)*)) => {
+        let action: $crate::html::Href = $action.into();
+        $crate::macros::add_attribute(&mut $stack, "formaction", action);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
     (@vtag $stack:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
         $crate::macros::add_attribute(&mut $stack, local_stringify!($attr), $val);
         html_impl! { @vtag $stack ($($tail)*) }
@@ -270,6 +304,77 @@ macro_rules! html_impl {
         $crate::macros::child_to_parent(&mut $stack, None);
         html_impl! { $stack ($($tail)*) }
     };
+    // PATTERN: { @if condition { markup } else if condition { markup } else { markup } }
+    // Plain `{ if ... }` and `{ match ... }` blocks are expressions (see below).
+    ($stack:ident ({ @if $($cond:tt)* } $($tail:tt)*)) => {
+        let node = html_impl! { @if () ($($cond)*) };
+        html_impl! { @child $stack (node) ($($tail)*) }
+    };
+    // PATTERN: { @match expression { pattern => { markup }, ... } }
+    ($stack:ident ({ @match $($expr:tt)* } $($tail:tt)*)) => {
+        let node = html_impl! { @match () ($($expr)*) };
+        html_impl! { @child $stack (node) ($($tail)*) }
+    };
+    (@if ($($cond:tt)*) ({ $($then:tt)* })) => {
+        if $($cond)* {
+            html_impl! { @branch ($($then)*) }
+        } else {
+            html_impl! { @branch () }
+        }
+    };
+    (@if ($($cond:tt)*) ({ $($then:tt)* } else { $($other:tt)* })) => {
+        if $($cond)* {
+            html_impl! { @branch ($($then)*) }
+        } else {
+            html_impl! { @branch ($($other)*) }
+        }
+    };
+    (@if ($($cond:tt)*) ({ $($then:tt)* } else if $($tail:tt)*)) => {
+        if $($cond)* {
+            html_impl! { @branch ($($then)*) }
+        } else {
+            html_impl! { @if () ($($tail)*) }
+        }
+    };
+    (@if ($($cond:tt)*) ($next:tt $($tail:tt)*)) => {
+        html_impl! { @if ($($cond)* $next) ($($tail)*) }
+    };
+    (@match ($($expr:tt)*) ({ $($arms:tt)* })) => {
+        html_impl! { @arms ($($expr)*) () ($($arms)*) }
+    };
+    (@match ($($expr:tt)*) ($next:tt $($tail:tt)*)) => {
+        html_impl! { @match ($($expr)* $next) ($($tail)*) }
+    };
+    (@arms ($($expr:tt)*) ($($done:tt)*) ($($pat:pat)|+ => { $($body:tt)* } , $($tail:tt)*)) => {
+        html_impl! { @arms ($($expr)*) ($($done)* $($pat)|+ => { html_impl! { @branch ($($body)*) } }) ($($tail)*) }
+    };
+    (@arms ($($expr:tt)*) ($($done:tt)*) ($($pat:pat)|+ => { $($body:tt)* } $($tail:tt)*)) => {
+        html_impl! { @arms ($($expr)*) ($($done)* $($pat)|+ => { html_impl! { @branch ($($body)*) } }) ($($tail)*) }
+    };
+    (@arms ($($expr:tt)*) ($($done:tt)*) ($($pat:pat)|+ if $guard:expr => { $($body:tt)* } , $($tail:tt)*)) => {
+        html_impl! { @arms ($($expr)*) ($($done)* $($pat)|+ if $guard => { html_impl! { @branch ($($body)*) } }) ($($tail)*) }
+    };
+    (@arms ($($expr:tt)*) ($($done:tt)*) ($($pat:pat)|+ if $guard:expr => { $($body:tt)* } $($tail:tt)*)) => {
+        html_impl! { @arms ($($expr)*) ($($done)* $($pat)|+ if $guard => { html_impl! { @branch ($($body)*) } }) ($($tail)*) }
+    };
+    (@arms ($($expr:tt)*) ($($done:tt)*) ()) => {
+        match $($expr)* { $($done)* }
+    };
+    // An empty branch renders an empty text node to keep the position in the DOM
+    (@branch ()) => {
+        $crate::virtual_dom::VNode::from($crate::virtual_dom::VText::new(String::new()))
+    };
+    (@branch ($($body:tt)+)) => {
+        $crate::virtual_dom::VNode::from(html! { $($body)+ })
+    };
+    (@child $stack:ident ($node:expr) ()) => {
+        $stack.push($node);
+        html_impl! { $stack () }
+    };
+    (@child $stack:ident ($node:expr) ($($tail:tt)*)) => {
+        $crate::macros::add_child(&mut $stack, $node);
+        html_impl! { $stack ($($tail)*) }
+    };
     // Support root text nodes: #313
     // Provides `html!` blocks with only expression inside
     ($stack:ident ({ $eval:expr })) => {
@@ -292,6 +397,76 @@ macro_rules! html_impl {
     };
 }
 
+/// This macro combines classes for `class` attribute of `html!` macro.
+/// It accepts any values which could be converted to `Classes`, for example
+/// `&str`, `String`, `Option` or `(class, bool)` pairs:
+///
+/// `html! { <button class=classes!("btn", ("active", self.on), self.extra.clone()),></button> }`
+#[macro_export]
+macro_rules! classes {
+    ($($class:expr),* $(,)?) => {
+        $crate::virtual_dom::Classes::new()$(.extend($class))*
+    };
+}
+
+/// This macro declares a function component: a stateless component which is
+/// rendered by a function of its properties. The component is re-rendered
+/// only when new properties are not equal to the previous ones.
+///
+/// ```ignore
+/// function_component! {
+///     pub fn Greeting(props: &GreetingProps) -> Html {
+///         html! { <p>{ format!("Hello, {}!", props.name) }</p> }
+///     }
+/// }
+/// ```
+///
+/// It's usable as any other component: `html! { <Greeting: name="World", /> }`.
+/// The properties argument could be omitted for components without properties.
+#[macro_export]
+macro_rules! function_component {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident() -> Html $body:block) => {
+        $crate::function_component! {
+            $(#[$attr])* $vis fn $name(_props: &()) -> Html $body
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($props:ident : & $ty:ty) -> Html $body:block) => {
+        $(#[$attr])*
+        $vis struct $name {
+            props: $ty,
+        }
+
+        impl $crate::html::Component for $name {
+            type Message = ();
+            type Properties = $ty;
+
+            fn create(props: Self::Properties, _: $crate::html::ComponentLink<Self>) -> Self {
+                $name { props }
+            }
+
+            fn update(&mut self, _: Self::Message) -> $crate::html::ShouldRender {
+                true
+            }
+
+            fn change(&mut self, props: Self::Properties) -> $crate::html::ShouldRender {
+                if self.props != props {
+                    self.props = props;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+
+        impl $crate::html::Renderable<$name> for $name {
+            fn view(&self) -> $crate::html::Html<Self> {
+                let $props: &$ty = &self.props;
+                $body
+            }
+        }
+    };
+}
+
 /// This macro implements JSX-like templates.
 #[macro_export]
 macro_rules! html {
@@ -316,17 +491,21 @@ pub fn unpack<COMP: Component>(mut stack: Stack<COMP>) -> VNode<COMP> {
 #[doc(hidden)]
 pub fn set_value_or_attribute<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, value: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
-        if vtag.tag().eq_ignore_ascii_case("input")
-        || vtag.tag().eq_ignore_ascii_case("textarea") {
-            vtag.set_value(&value)
-        } else {
-            vtag.add_attribute("value", &value)
-        }
+        set_value_of(vtag, value);
     } else {
         panic!("no tag to set value: {}", value.to_string());
     }
 }
 
+fn set_value_of<COMP: Component, T: ToString>(vtag: &mut VTag<COMP>, value: T) {
+    if vtag.tag().eq_ignore_ascii_case("input")
+    || vtag.tag().eq_ignore_ascii_case("textarea") {
+        vtag.set_value(&value)
+    } else {
+        vtag.add_attribute("value", &value)
+    }
+}
+
 #[doc(hidden)]
 pub fn set_kind<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, value: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
@@ -358,21 +537,54 @@ pub fn add_attribute<COMP: Component, T: ToString>(
     }
 }
 
+/// This method sets attributes from a map (or any other iterator of pairs).
+/// `class` is added to classes of the element, URL attributes are checked
+/// with `Href`, and `value`, `type` and `checked` are set as properties
+/// the same way as in `html!` macro. `checked` is set unless it's `"false"`.
+#[doc(hidden)]
+pub fn spread_attributes<COMP, I, K, V>(stack: &mut Stack<COMP>, attrs: I)
+where
+    COMP: Component,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: ToString,
+{
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        for (name, value) in attrs {
+            let name = name.as_ref();
+            let value = value.to_string();
+            match name {
+                "class" => vtag.add_classes(value),
+                "value" => set_value_of(vtag, value),
+                "type" => vtag.set_kind(&value),
+                "checked" => vtag.set_checked(value != "false"),
+                "src" => vtag.add_attribute(name, &Href::from(value).to_source_string()),
+                "href" | "action" | "formaction" => {
+                    vtag.add_attribute(name, &Href::from(value));
+                }
+                _ => vtag.add_attribute(name, &value),
+            }
+        }
+    } else {
+        panic!("no tag to spread attributes");
+    }
+}
+
 #[doc(hidden)]
-pub fn append_class<COMP: Component, T: AsRef<str>>(stack: &mut Stack<COMP>, class: T) {
+pub fn append_class<COMP: Component, T: Into<Classes>>(stack: &mut Stack<COMP>, class: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
-        vtag.add_class(class.as_ref());
+        vtag.add_classes(class);
     } else {
-        panic!("no tag to attach class: {}", class.as_ref());
+        panic!("no tag to attach class: {:?}", class.into());
     }
 }
 
 #[doc(hidden)]
-pub fn set_classes<COMP: Component, T: AsRef<str>>(stack: &mut Stack<COMP>, classes: T) {
+pub fn set_classes<COMP: Component, T: Into<Classes>>(stack: &mut Stack<COMP>, classes: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
-        vtag.set_classes(classes.as_ref());
+        vtag.set_classes(classes);
     } else {
-        panic!("no tag to set classes: {}", classes.as_ref());
+        panic!("no tag to set classes: {:?}", classes.into());
     }
 }
 
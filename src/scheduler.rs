@@ -49,16 +49,36 @@ impl Scheduler {
 
     pub(crate) fn put_and_try_run(&self, runnable: Box<dyn Runnable>) {
         self.sequence.borrow_mut().push_back(runnable);
-        if self.lock.compare_and_swap(false, true, Ordering::Relaxed) == false {
-            loop {
-                let do_next = self.sequence.borrow_mut().pop_front();
-                if let Some(mut runnable) = do_next {
-                    runnable.run();
-                } else {
-                    break;
-                }
-            }
+        if self.try_lock() {
+            self.run_all();
+            self.lock.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Runs a routine immediately, but keeps the scheduler locked while it runs.
+    /// Runnables which are put by the routine are run after it.
+    pub(crate) fn run_locked<F: FnOnce()>(&self, routine: F) {
+        if self.try_lock() {
+            routine();
+            self.run_all();
             self.lock.store(false, Ordering::Relaxed);
+        } else {
+            routine();
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        self.lock.compare_and_swap(false, true, Ordering::Relaxed) == false
+    }
+
+    fn run_all(&self) {
+        loop {
+            let do_next = self.sequence.borrow_mut().pop_front();
+            if let Some(mut runnable) = do_next {
+                runnable.run();
+            } else {
+                break;
+            }
         }
     }
 }
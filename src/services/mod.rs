@@ -23,6 +23,8 @@ pub use self::storage::StorageService;
 pub use self::timeout::TimeoutService;
 pub use self::websocket::WebSocketService;
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// An universal task of a service.
@@ -34,6 +36,64 @@ pub trait Task: Drop {
     fn cancel(&mut self);
 }
 
+/// A key of a task in a registry of a component.
+pub type TaskKey = Cow<'static, str>;
+
+/// Tasks owned by a component. All of them are canceled when the component is destroyed.
+/// Components use it through `ComponentLink::register_task` and `ComponentLink::add_task`.
+#[derive(Default)]
+pub struct TaskRegistry {
+    keyed: HashMap<TaskKey, Box<dyn Task>>,
+    anonymous: Vec<Box<dyn Task>>,
+}
+
+impl TaskRegistry {
+    /// Adds a task with a key and cancels a previous task with the same key.
+    pub fn insert(&mut self, key: TaskKey, task: Box<dyn Task>) {
+        if let Some(previous) = self.keyed.insert(key, task) {
+            cancel(previous);
+        }
+    }
+
+    /// Adds a task without a key. Inactive tasks are removed to don't grow forever.
+    pub fn push(&mut self, task: Box<dyn Task>) {
+        self.anonymous.retain(|task| task.is_active());
+        self.anonymous.push(task);
+    }
+
+    /// Cancels a task with the key. Returns `false` if there was no such task.
+    pub fn cancel(&mut self, key: &str) -> bool {
+        match self.keyed.remove(key) {
+            Some(task) => {
+                cancel(task);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks that a task with the key exists and is active.
+    pub fn is_active(&self, key: &str) -> bool {
+        self.keyed.get(key).map(|task| task.is_active()).unwrap_or(false)
+    }
+
+    /// Cancels all tasks.
+    pub fn cancel_all(&mut self) {
+        for (_, task) in self.keyed.drain() {
+            cancel(task);
+        }
+        for task in self.anonymous.drain(..) {
+            cancel(task);
+        }
+    }
+}
+
+fn cancel(mut task: Box<dyn Task>) {
+    if task.is_active() {
+        task.cancel();
+    }
+}
+
 #[doc(hidden)]
 fn to_ms(duration: Duration) -> u32 {
     let ms = duration.subsec_nanos() / 1_000_000;
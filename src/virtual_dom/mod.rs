@@ -3,19 +3,25 @@
 pub mod vcomp;
 pub mod vlist;
 pub mod vnode;
+pub mod vraw;
 pub mod vtag;
 pub mod vtext;
+pub mod vwidget;
+mod snapshot;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fmt;
 use stdweb::web::{Element, EventListenerHandle, Node};
 
 pub use self::vcomp::VComp;
 pub use self::vlist::VList;
 pub use self::vnode::VNode;
+pub use self::vraw::VRaw;
 pub use self::vtag::VTag;
 pub use self::vtext::VText;
+pub use self::vwidget::{VWidget, Widget};
 use html::{Component, Scope};
+pub(crate) use self::snapshot::NodeState;
 
 /// `Listener` trait is an universal implementation of an event listener
 /// which helps to bind Rust-listener to JS-listener (DOM).
@@ -37,10 +43,145 @@ impl<COMP: Component> fmt::Debug for dyn Listener<COMP> {
 type Listeners<COMP> = Vec<Box<dyn Listener<COMP>>>;
 
 /// A map of attributes.
-type Attributes = HashMap<String, String>;
+pub type Attributes = HashMap<String, String>;
 
-/// A set of classes.
-type Classes = HashSet<String>;
+/// A set of classes of an element. It keeps the order of insertion to patch
+/// [classList](https://developer.mozilla.org/en-US/docs/Web/API/Element/classList)
+/// of an element in the same order every time.
+///
+/// It could be created from `&str` or `String` (with whitespace-separated classes),
+/// `Vec`, `Option`, tuples and `(class, bool)` pairs which add a class only if
+/// a flag is `true`. Use `classes!` macro to combine them:
+///
+/// ```
+/// # #[macro_use] extern crate yew;
+/// # fn main() {
+/// let active = true;
+/// let extra = Some("large".to_string());
+/// let classes = classes!("btn btn-primary", ("active", active), extra);
+/// assert!(classes.contains("active"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Classes {
+    set: Vec<String>,
+}
+
+impl Classes {
+    /// Creates an empty set of classes.
+    pub fn new() -> Self {
+        Classes { set: Vec::new() }
+    }
+
+    /// Adds a class (or whitespace-separated classes) to the set
+    /// if it doesn't contain it yet.
+    pub fn push(&mut self, class: &str) {
+        for class in class.split_whitespace() {
+            if !self.contains(class) {
+                self.set.push(class.to_owned());
+            }
+        }
+    }
+
+    /// Adds all classes from another set.
+    pub fn extend<T: Into<Classes>>(mut self, other: T) -> Self {
+        for class in other.into().set {
+            if !self.contains(&class) {
+                self.set.push(class);
+            }
+        }
+        self
+    }
+
+    /// Checks the set contains a class.
+    pub fn contains(&self, class: &str) -> bool {
+        self.set.iter().any(|item| item == class)
+    }
+
+    /// Checks the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns an iterator over classes in the order of insertion.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, String> {
+        self.set.iter()
+    }
+}
+
+impl PartialEq for Classes {
+    /// Compares classes regardless of their order.
+    fn eq(&self, other: &Classes) -> bool {
+        self.set.len() == other.set.len() && self.iter().all(|class| other.contains(class))
+    }
+}
+
+impl<'a> From<&'a str> for Classes {
+    fn from(classes: &'a str) -> Self {
+        let mut set = Classes::new();
+        set.push(classes);
+        set
+    }
+}
+
+impl From<String> for Classes {
+    fn from(classes: String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Classes {
+    fn from(classes: &'a String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<T: Into<Classes>> From<Option<T>> for Classes {
+    fn from(classes: Option<T>) -> Self {
+        classes.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl<T: Into<Classes>> From<Vec<T>> for Classes {
+    fn from(classes: Vec<T>) -> Self {
+        classes.into_iter().fold(Classes::new(), Classes::extend)
+    }
+}
+
+impl<'a> From<(&'a str, bool)> for Classes {
+    fn from((class, enabled): (&'a str, bool)) -> Self {
+        if enabled {
+            Classes::from(class)
+        } else {
+            Classes::new()
+        }
+    }
+}
+
+impl From<(String, bool)> for Classes {
+    fn from((class, enabled): (String, bool)) -> Self {
+        Classes::from((class.as_str(), enabled))
+    }
+}
+
+macro_rules! impl_classes_from_tuple {
+    ($($item:ident),+) => {
+        impl<$($item: Into<Classes>),+> From<($($item,)+)> for Classes {
+            #[allow(non_snake_case)]
+            fn from(($($item,)+): ($($item,)+)) -> Self {
+                Classes::new()$(.extend($item))+
+            }
+        }
+    };
+}
+
+impl_classes_from_tuple!(A, B);
+impl_classes_from_tuple!(A, B, C);
+impl_classes_from_tuple!(A, B, C, D);
+impl_classes_from_tuple!(A, B, C, D, E);
+impl_classes_from_tuple!(A, B, C, D, E, F);
+impl_classes_from_tuple!(A, B, C, D, E, F, G);
+impl_classes_from_tuple!(A, B, C, D, E, F, G, H);
 
 /// Patch for DOM node modification.
 enum Patch<ID, T> {
@@ -66,10 +207,6 @@ enum Reform {
     Before(Option<Node>),
 }
 
-// TODO What about to implement `VDiff` for `Element`?
-// In makes possible to include ANY element into the tree.
-// `Ace` editor embedding for example?
-
 /// This trait provides features to update a tree by other tree comparsion.
 pub trait VDiff {
     /// The component which this instance put into.
@@ -0,0 +1,99 @@
+//! This module contains helpers to keep a user-visible state of DOM nodes
+//! (focus, selection range and scroll offsets) across re-renders.
+
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use stdweb::web::Node;
+
+/// A state of a node captured before it was patched or moved in the DOM.
+pub(crate) struct NodeState(Value);
+
+impl NodeState {
+    /// Captures a state of the node.
+    pub(crate) fn capture(node: &Node) -> Self {
+        let state = js! {
+            var node = @{node};
+            var active = document.activeElement;
+            // Active element of a document could be a host of a shadow tree
+            while (active && active.shadowRoot && active.shadowRoot.activeElement) {
+                active = active.shadowRoot.activeElement;
+            }
+            var state = {
+                node: node,
+                focused: active === node,
+                top: node.scrollTop || 0,
+                left: node.scrollLeft || 0,
+                start: null,
+                end: null,
+                direction: null,
+            };
+            try {
+                if (typeof node.selectionStart === "number") {
+                    state.start = node.selectionStart;
+                    state.end = node.selectionEnd;
+                    state.direction = node.selectionDirection;
+                }
+            } catch (e) {
+                // Some input types (like `email`) don't support selection
+            }
+            return state;
+        };
+        NodeState(state)
+    }
+
+    /// Captures a state of the currently focused element if it exists.
+    pub(crate) fn active() -> Option<Self> {
+        let active = js! {
+            var active = document.activeElement;
+            while (active && active.shadowRoot && active.shadowRoot.activeElement) {
+                active = active.shadowRoot.activeElement;
+            }
+            if (!active || active === document.body || active === document.documentElement) {
+                return null;
+            }
+            return active;
+        };
+        let active: Option<Node> = active.try_into().unwrap_or(None);
+        active.map(|node| NodeState::capture(&node))
+    }
+
+    /// Restores the captured state if the node is still in a document.
+    /// Focus is restored only when the node was moved, a recreated node
+    /// can't get the focus back.
+    pub(crate) fn restore(self) {
+        let state = self.0;
+        js! { @(no_return)
+            var state = @{state};
+            var node = state.node;
+            if (!node.isConnected) {
+                return;
+            }
+            if (node.scrollTop !== state.top) {
+                node.scrollTop = state.top;
+            }
+            if (node.scrollLeft !== state.left) {
+                node.scrollLeft = state.left;
+            }
+            if (!state.focused) {
+                return;
+            }
+            var root = node.getRootNode();
+            if (root.activeElement !== node) {
+                try {
+                    node.focus({ preventScroll: true });
+                } catch (e) {
+                    node.focus();
+                }
+            }
+            if (state.start !== null) {
+                try {
+                    if (node.selectionStart !== state.start || node.selectionEnd !== state.end) {
+                        node.setSelectionRange(state.start, state.end, state.direction);
+                    }
+                } catch (e) {
+                    // The type of the input could be changed during the render
+                }
+            }
+        };
+    }
+}
@@ -4,8 +4,7 @@ use std::any::TypeId;
 use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
-use stdweb::unstable::TryInto;
-use stdweb::web::{document, Element, INode, Node};
+use stdweb::web::{document, INode, Node};
 use html::{Component, ComponentUpdate, Scope, NodeCell, Renderable};
 use callback::Callback;
 use super::{Reform, VDiff, VNode};
@@ -15,7 +14,7 @@ struct Hidden;
 type AnyProps = (TypeId, *mut Hidden);
 
 /// The method generates an instance of a (child) component.
-type Generator = dyn FnMut(Element, Node, AnyProps);
+type Generator = dyn FnMut(Node, Node, AnyProps);
 
 /// A reference to unknown activator which will be attached later with a generator function.
 type LazyActivator<COMP> = Rc<RefCell<Option<Scope<COMP>>>>;
@@ -44,7 +43,7 @@ impl<COMP: Component> VComp<COMP> {
         // This function creates and mounts a new component instance
         let generator = {
             let lazy_activator = lazy_activator.clone();
-            move |element, ancestor: Node, (type_id, raw): AnyProps| {
+            move |parent, ancestor: Node, (type_id, raw): AnyProps| {
                 if type_id != TypeId::of::<CHILD>() {
                     panic!("tried to unpack properties of the other component");
                 }
@@ -56,7 +55,7 @@ impl<COMP: Component> VComp<COMP> {
                 let env = scope.clone();
                 *lazy_activator.borrow_mut() = Some(env);
                 scope.mount_in_place(
-                    element,
+                    parent,
                     Some(VNode::VRef(ancestor)),
                     Some(occupied.clone()),
                     Some(props),
@@ -90,10 +89,10 @@ impl<COMP: Component> VComp<COMP> {
         let destroyer = {
             let lazy_activator = lazy_activator;
             move || {
-                lazy_activator.borrow_mut()
-                    .as_mut()
-                    .expect("activator for child scope was not set (destroyer)")
-                    .send(ComponentUpdate::Destroy);
+                let mut scope = lazy_activator.borrow()
+                    .clone()
+                    .expect("activator for child scope was not set (destroyer)");
+                scope.destroy();
             }
         };
         let properties = Default::default();
@@ -205,13 +204,8 @@ where
         ancestor: Node, // Any dummy expected
         props: AnyProps,
     ) {
-        let element: Element = parent
-            .as_node()
-            .as_ref()
-            .to_owned()
-            .try_into()
-            .expect("element expected to mount VComp");
-        (self.generator)(element, ancestor, props);
+        let parent = parent.as_node().to_owned();
+        (self.generator)(parent, ancestor, props);
     }
 
     fn send_props(&mut self, props: AnyProps) {
@@ -227,17 +221,18 @@ where
 
     /// Remove VComp from parent.
     fn detach(&mut self, parent: &Node) -> Option<Node> {
+        // Keep the sibling, because the component removes its node from the parent
+        let sibling = self.cell.borrow().as_ref().and_then(|node| node.next_sibling());
         // Destroy the loop. It's impossible to use `Drop`,
         // because parts can be reused with `grab_sender_of`.
-        (self.destroyer)(); // TODO Chech it works
-        // Keep the sibling in the cell and send a message `Drop` to a loop
-        self.cell.borrow_mut().take().and_then(|node| {
-            let sibling = node.next_sibling();
-            parent
-                .remove_child(&node)
-                .expect("can't remove the component");
-            sibling
-        })
+        (self.destroyer)();
+        // The node is still here if the component is busy and destroying was scheduled
+        if let Some(node) = self.cell.borrow_mut().take() {
+            if parent.remove_child(&node).is_err() {
+                warn!("Node not found to remove VComp");
+            }
+        }
+        sibling
     }
 
     /// Renders independent component over DOM `Element`.
@@ -1,6 +1,6 @@
 //! This module contains the implementation of abstract virtual node.
 
-use super::{VComp, VDiff, VList, VTag, VText};
+use super::{NodeState, VComp, VDiff, VList, VRaw, VTag, VText, VWidget};
 use html::{Component, Renderable, Scope};
 use std::cmp::PartialEq;
 use std::fmt;
@@ -16,6 +16,10 @@ pub enum VNode<COMP: Component> {
     VComp(VComp<COMP>),
     /// A holder for a list of other nodes.
     VList(VList<COMP>),
+    /// A bind between `VRaw` and a host `Element` with a raw HTML content.
+    VRaw(VRaw<COMP>),
+    /// A bind between `VWidget` and a host `Element` of an external widget.
+    VWidget(VWidget<COMP>),
     /// A holder for any `Node` (necessary for replacing node).
     VRef(Node),
 }
@@ -30,6 +34,8 @@ impl<COMP: Component> VDiff for VNode<COMP> {
             VNode::VText(ref mut vtext) => vtext.detach(parent),
             VNode::VComp(ref mut vcomp) => vcomp.detach(parent),
             VNode::VList(ref mut vlist) => vlist.detach(parent),
+            VNode::VRaw(ref mut vraw) => vraw.detach(parent),
+            VNode::VWidget(ref mut vwidget) => vwidget.detach(parent),
             VNode::VRef(ref node) => {
                 let sibling = node.next_sibling();
                 parent
@@ -52,11 +58,19 @@ impl<COMP: Component> VDiff for VNode<COMP> {
             VNode::VText(ref mut vtext) => vtext.apply(parent, precursor, ancestor, env),
             VNode::VComp(ref mut vcomp) => vcomp.apply(parent, precursor, ancestor, env),
             VNode::VList(ref mut vlist) => vlist.apply(parent, precursor, ancestor, env),
+            VNode::VRaw(ref mut vraw) => vraw.apply(parent, precursor, ancestor, env),
+            VNode::VWidget(ref mut vwidget) => vwidget.apply(parent, precursor, ancestor, env),
             VNode::VRef(ref mut node) => {
                 let sibling = match ancestor {
+                    // The same node is still in place, don't move it
+                    // to keep the focus and the scroll offsets.
+                    Some(VNode::VRef(ref n)) if n == node => {
+                        return Some(node.to_owned());
+                    }
                     Some(mut n) => n.detach(parent),
                     None => None,
                 };
+                let state = NodeState::capture(node);
                 if let Some(sibling) = sibling {
                     parent
                         .insert_before(node, &sibling)
@@ -64,6 +78,7 @@ impl<COMP: Component> VDiff for VNode<COMP> {
                 } else {
                     parent.append_child(node);
                 }
+                state.restore();
 
                 Some(node.to_owned())
             }
@@ -89,6 +104,18 @@ impl<COMP: Component> From<VTag<COMP>> for VNode<COMP> {
     }
 }
 
+impl<COMP: Component> From<VRaw<COMP>> for VNode<COMP> {
+    fn from(vraw: VRaw<COMP>) -> Self {
+        VNode::VRaw(vraw)
+    }
+}
+
+impl<COMP: Component> From<VWidget<COMP>> for VNode<COMP> {
+    fn from(vwidget: VWidget<COMP>) -> Self {
+        VNode::VWidget(vwidget)
+    }
+}
+
 impl<COMP: Component> From<VComp<COMP>> for VNode<COMP> {
     fn from(vcomp: VComp<COMP>) -> Self {
         VNode::VComp(vcomp)
@@ -114,6 +141,8 @@ impl<COMP: Component> fmt::Debug for VNode<COMP> {
             VNode::VText(ref vtext) => vtext.fmt(f),
             VNode::VComp(_) => "Component<>".fmt(f),
             VNode::VList(_) => "List<>".fmt(f),
+            VNode::VRaw(ref vraw) => vraw.fmt(f),
+            VNode::VWidget(ref vwidget) => vwidget.fmt(f),
             VNode::VRef(_) => "NodeReference<>".fmt(f),
         }
     }
@@ -130,6 +159,14 @@ impl<COMP: Component> PartialEq for VNode<COMP> {
                 VNode::VText(ref vtext_b) => vtext_a == vtext_b,
                 _ => false,
             },
+            VNode::VRaw(ref vraw_a) => match *other {
+                VNode::VRaw(ref vraw_b) => vraw_a == vraw_b,
+                _ => false,
+            },
+            VNode::VWidget(ref vwidget_a) => match *other {
+                VNode::VWidget(ref vwidget_b) => vwidget_a == vwidget_b,
+                _ => false,
+            },
             _ => {
                 // TODO Implement it
                 false
@@ -0,0 +1,406 @@
+//! This module contains the implementation of a virtual raw HTML node `VRaw`.
+
+use std::borrow::Cow;
+use std::cmp::PartialEq;
+use std::fmt;
+use std::marker::PhantomData;
+use stdweb::web::{document, Element, INode, Node};
+use html::{Component, Href, Scope};
+use super::{Reform, VDiff, VNode};
+
+/// A type for a raw HTML content which is set to
+/// [innerHTML](https://developer.mozilla.org/en-US/docs/Web/API/Element/innerHTML)
+/// of a host element.
+pub struct VRaw<COMP: Component> {
+    /// A tag of the host element.
+    tag: Cow<'static, str>,
+    /// Contains HTML of the node.
+    pub html: String,
+    /// Strip scripts, event handlers and forbidden URLs before rendering.
+    pub sanitize: bool,
+    /// A reference to the host `Element`.
+    pub reference: Option<Element>,
+    _comp: PhantomData<COMP>,
+}
+
+impl<COMP: Component> VRaw<COMP> {
+    /// Creates a new raw node with a trusted HTML content inside of a `div` element.
+    pub fn new(html: String) -> Self {
+        VRaw {
+            tag: "div".into(),
+            html,
+            sanitize: false,
+            reference: None,
+            _comp: PhantomData,
+        }
+    }
+
+    /// Creates a new raw node with an untrusted HTML content
+    /// which will be sanitized before rendering.
+    pub fn sanitized(html: String) -> Self {
+        let mut vraw = VRaw::new(html);
+        vraw.sanitize = true;
+        vraw
+    }
+
+    /// Sets a tag of the host element (`div` by default).
+    pub fn set_tag<S: Into<Cow<'static, str>>>(&mut self, tag: S) {
+        self.tag = tag.into();
+    }
+
+    /// Returns tag of the host element.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    fn set_inner_html(&self, element: &Element) {
+        let html = if self.sanitize {
+            sanitize(&self.html)
+        } else {
+            self.html.clone()
+        };
+        js!( @(no_return) @{element}.innerHTML = @{html}; );
+    }
+}
+
+impl<COMP: Component> VDiff for VRaw<COMP> {
+    type Component = COMP;
+
+    /// Remove VRaw from parent.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let node = self.reference.take()
+            .expect("tried to remove not rendered VRaw from DOM");
+        let sibling = node.next_sibling();
+        if parent.remove_child(&node).is_err() {
+            warn!("Node not found to remove VRaw");
+        }
+        sibling
+    }
+
+    /// Renders raw HTML into the host element, but only if the content had changed.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let reform = {
+            match ancestor {
+                Some(VNode::VRaw(mut vraw)) => {
+                    if self.tag == vraw.tag {
+                        self.reference = vraw.reference.take();
+                        if self.html != vraw.html || self.sanitize != vraw.sanitize {
+                            if let Some(ref element) = self.reference {
+                                self.set_inner_html(element);
+                            }
+                        }
+                        Reform::Keep
+                    } else {
+                        let node = vraw.detach(parent);
+                        Reform::Before(node)
+                    }
+                }
+                Some(mut vnode) => {
+                    let node = vnode.detach(parent);
+                    Reform::Before(node)
+                }
+                None => Reform::Before(None),
+            }
+        };
+        match reform {
+            Reform::Keep => {}
+            Reform::Before(before) => {
+                let element = document()
+                    .create_element(&self.tag)
+                    .expect("can't create element for vraw");
+                self.set_inner_html(&element);
+                if let Some(sibling) = before {
+                    parent
+                        .insert_before(&element, &sibling)
+                        .expect("can't insert raw html before sibling");
+                } else {
+                    let precursor = precursor.and_then(|before| before.next_sibling());
+                    if let Some(precursor) = precursor {
+                        parent
+                            .insert_before(&element, &precursor)
+                            .expect("can't insert raw html before precursor");
+                    } else {
+                        parent.append_child(&element);
+                    }
+                }
+                self.reference = Some(element);
+            }
+        }
+        self.reference.as_ref().map(|e| e.as_node().to_owned())
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VRaw<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VRaw {{ tag: {}, html: {} }}", self.tag, self.html)
+    }
+}
+
+impl<COMP: Component> PartialEq for VRaw<COMP> {
+    fn eq(&self, other: &VRaw<COMP>) -> bool {
+        self.tag == other.tag && self.html == other.html && self.sanitize == other.sanitize
+    }
+}
+
+/// Elements which are removed with their content. SVG animations are dropped,
+/// because they could set a URL attribute with `to`, `from`, `by` or `values`.
+/// Names are lowercase, because names of parsed tags are lowercased.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "noscript",
+    "animate", "set", "animatemotion", "animatetransform",
+];
+
+/// Attributes which contain URLs.
+const URL_ATTRIBUTES: &[&str] = &[
+    "href", "src", "action", "formaction", "xlink:href", "background", "poster", "srcset",
+];
+
+/// Removes `script` (and other embedding) elements, event handler attributes
+/// (like `onclick`) and URLs with forbidden schemes (like `javascript:`,
+/// see `Href::set_allowed_schemes`) from an untrusted HTML.
+pub fn sanitize(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(pos) = rest.find('<') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if rest.starts_with("<!--") {
+            // Drop comments, because they could hide conditional markup
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+        match parse_tag(rest) {
+            Some(tag) => {
+                rest = &rest[tag.length..];
+                if DROPPED_ELEMENTS.contains(&tag.name.as_str()) {
+                    if !tag.closing && !tag.self_closing {
+                        rest = skip_element(rest, &tag.name);
+                    }
+                    continue;
+                }
+                write_tag(&mut output, &tag);
+            }
+            None => {
+                output.push_str("&lt;");
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: Vec<(String, Option<String>)>,
+    length: usize,
+}
+
+/// Parses a tag at the start of the input.
+fn parse_tag(input: &str) -> Option<Tag> {
+    let bytes = input.as_bytes();
+    let mut pos = 1;
+    let closing = bytes.get(pos) == Some(&b'/');
+    if closing {
+        pos += 1;
+    }
+    if !byte_is(bytes, pos, |b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let start = pos;
+    while byte_is(bytes, pos, |b| !b.is_ascii_whitespace() && b != b'/' && b != b'>') {
+        pos += 1;
+    }
+    let name = input[start..pos].to_ascii_lowercase();
+    let mut attributes = Vec::new();
+    let mut self_closing = false;
+    loop {
+        while byte_is(bytes, pos, |b| b.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            None => return None,
+            Some(b'>') => {
+                pos += 1;
+                break;
+            }
+            Some(b'/') => {
+                self_closing = true;
+                pos += 1;
+                continue;
+            }
+            Some(_) => {}
+        }
+        let start = pos;
+        while byte_is(bytes, pos, |b| {
+            !b.is_ascii_whitespace() && b != b'=' && b != b'>' && b != b'/'
+        }) {
+            pos += 1;
+        }
+        if start == pos {
+            // Skip a garbage like `=` without a name
+            pos += 1;
+            continue;
+        }
+        let attr = input[start..pos].to_ascii_lowercase();
+        while byte_is(bytes, pos, |b| b.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        if bytes.get(pos) != Some(&b'=') {
+            attributes.push((attr, None));
+            continue;
+        }
+        pos += 1;
+        while byte_is(bytes, pos, |b| b.is_ascii_whitespace()) {
+            pos += 1;
+        }
+        let value = match bytes.get(pos) {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let start = pos + 1;
+                let end = input[start..].find(quote as char)? + start;
+                pos = end + 1;
+                &input[start..end]
+            }
+            _ => {
+                let start = pos;
+                while byte_is(bytes, pos, |b| !b.is_ascii_whitespace() && b != b'>') {
+                    pos += 1;
+                }
+                &input[start..pos]
+            }
+        };
+        attributes.push((attr, Some(decode_entities(value))));
+    }
+    Some(Tag {
+        name,
+        closing,
+        self_closing,
+        attributes,
+        length: pos,
+    })
+}
+
+/// Checks a byte at the position.
+fn byte_is<F: Fn(u8) -> bool>(bytes: &[u8], pos: usize, check: F) -> bool {
+    match bytes.get(pos) {
+        Some(&b) => check(b),
+        None => false,
+    }
+}
+
+/// Skips everything till the closing tag of the element.
+fn skip_element<'a>(input: &'a str, name: &str) -> &'a str {
+    let lowercase = input.to_ascii_lowercase();
+    let closing = format!("</{}", name);
+    match lowercase.find(&closing) {
+        Some(pos) => {
+            let rest = &input[pos..];
+            match rest.find('>') {
+                Some(end) => &rest[end + 1..],
+                None => "",
+            }
+        }
+        None => "",
+    }
+}
+
+fn write_tag(output: &mut String, tag: &Tag) {
+    output.push('<');
+    if tag.closing {
+        output.push('/');
+    }
+    output.push_str(&tag.name);
+    if !tag.closing {
+        for (name, value) in &tag.attributes {
+            if name.starts_with("on") {
+                continue;
+            }
+            if let Some(value) = value {
+                if URL_ATTRIBUTES.contains(&name.as_str()) && !Href::is_allowed(value) {
+                    continue;
+                }
+                output.push(' ');
+                output.push_str(name);
+                output.push_str("=\"");
+                output.push_str(&escape_attribute(value));
+                output.push('"');
+            } else {
+                output.push(' ');
+                output.push_str(name);
+            }
+        }
+        if tag.self_closing {
+            output.push_str(" /");
+        }
+    }
+    output.push('>');
+}
+
+/// Decodes numeric character references and entities which could be used
+/// to hide a scheme of a URL.
+fn decode_entities(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find('&') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let end = match rest.find(';') {
+            Some(end) if end < 12 => end,
+            _ => {
+                output.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let decoded = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+            u32::from_str_radix(hex, 16).ok().and_then(::std::char::from_u32)
+        } else if let Some(dec) = entity.strip_prefix('#') {
+            dec.parse().ok().and_then(::std::char::from_u32)
+        } else {
+            match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "colon" => Some(':'),
+                "tab" => Some('\t'),
+                "newline" => Some('\n'),
+                _ => None,
+            }
+        };
+        match decoded {
+            Some(c) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
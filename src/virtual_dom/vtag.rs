@@ -78,17 +78,21 @@ impl<COMP: Component> VTag<COMP> {
     /// [Element.classList.add](https://developer.mozilla.org/en-US/docs/Web/API/Element/classList)
     /// call later.
     pub fn add_class(&mut self, class: &str) {
-        let class = class.trim();
-        if !class.is_empty() {
-            self.classes.insert(class.into());
-        }
+        self.classes.push(class);
+    }
+
+    /// Adds classes to this virtual node. Accepts anything that could be
+    /// converted to `Classes`, see `classes!` macro.
+    pub fn add_classes<T: Into<Classes>>(&mut self, classes: T) {
+        let current = ::std::mem::replace(&mut self.classes, Classes::new());
+        self.classes = current.extend(classes);
     }
 
-    /// Add classes to this virtual node. Actually it will set by
+    /// Sets classes of this virtual node. Actually it will set by
     /// [Element.classList.add](https://developer.mozilla.org/en-US/docs/Web/API/Element/classList)
     /// call later.
-    pub fn set_classes(&mut self, classes: &str) {
-        self.classes = classes.split_whitespace().map(String::from).collect();
+    pub fn set_classes<T: Into<Classes>>(&mut self, classes: T) {
+        self.classes = classes.into();
     }
 
     /// Sets `value` for an
@@ -138,12 +142,14 @@ impl<COMP: Component> VTag<COMP> {
         if let &mut Some(ref ancestor) = ancestor {
             // Only change what is necessary.
             let to_add = self.classes
-                .difference(&ancestor.classes)
+                .iter()
+                .filter(|class| !ancestor.classes.contains(class))
                 .map(|class| Patch::Add(class.to_owned(), ()));
             changes.extend(to_add);
             let to_remove = ancestor
                 .classes
-                .difference(&self.classes)
+                .iter()
+                .filter(|class| !self.classes.contains(class))
                 .map(|class| Patch::Remove(class.to_owned()));
             changes.extend(to_remove);
         } else {
@@ -295,10 +301,10 @@ impl<COMP: Component> VTag<COMP> {
             if let Some(change) = self.diff_value(ancestor) {
                 match change {
                     Patch::Add(kind, _) | Patch::Replace(kind, _) => {
-                        input.set_raw_value(&kind);
+                        set_value(element, &kind);
                     }
                     Patch::Remove(_) => {
-                        input.set_raw_value("");
+                        set_value(element, "");
                     }
                 }
             }
@@ -306,14 +312,14 @@ impl<COMP: Component> VTag<COMP> {
             // IMPORTANT! This parameters have to be set every time
             // to prevent strange behaviour in browser when DOM changed
             set_checked(&input, self.checked);
-        } else if let Ok(tae) = TextAreaElement::try_from(element.clone()) {
+        } else if TextAreaElement::try_from(element.clone()).is_ok() {
             if let Some(change) = self.diff_value(ancestor) {
                 match change {
                     Patch::Add(value, _) | Patch::Replace(value, _) => {
-                        tae.set_value(&value);
+                        set_value(element, &value);
                     }
                     Patch::Remove(_) => {
-                        tae.set_value("");
+                        set_value(element, "");
                     }
                 }
             }
@@ -328,6 +334,14 @@ impl<COMP: Component> VDiff for VTag<COMP> {
     fn detach(&mut self, parent: &Node) -> Option<Node> {
         let node = self.reference.take()
             .expect("tried to remove not rendered VTag from DOM");
+        for handle in self.captured.drain(..) {
+            handle.remove();
+        }
+        // Detach children to destroy nested components and widgets
+        let element = node.as_node();
+        for mut child in self.childs.drain(..) {
+            child.detach(element);
+        }
         let sibling = node.next_sibling();
         if parent.remove_child(&node).is_err() {
             warn!("Node not found to remove VTag");
@@ -474,6 +488,43 @@ fn remove_attribute(element: &Element, name: &str) {
     js!( @(no_return) @{element}.removeAttribute( @{name} ); );
 }
 
+/// Sets `value` of an `InputElement` or a `TextAreaElement` only if it differs
+/// from the actual one. Rewriting the same value moves the caret to the end,
+/// so the selection range of a focused element is kept.
+fn set_value(element: &Element, value: &str) {
+    js! { @(no_return)
+        var element = @{element};
+        var value = @{value};
+        if (element.value === value) {
+            return;
+        }
+        var start = null;
+        var end = null;
+        var direction = null;
+        var root = element.getRootNode ? element.getRootNode() : document;
+        if (root.activeElement === element) {
+            try {
+                start = element.selectionStart;
+                end = element.selectionEnd;
+                direction = element.selectionDirection;
+            } catch (e) {
+                // Some input types (like `email`) don't support selection
+            }
+        }
+        element.value = value;
+        if (typeof start === "number") {
+            try {
+                element.setSelectionRange(
+                    Math.min(start, value.length),
+                    Math.min(end, value.length),
+                    direction
+                );
+            } catch (e) {
+            }
+        }
+    };
+}
+
 /// Set `checked` value for the `InputElement`.
 fn set_checked(input: &InputElement, value: bool) {
     js!( @(no_return) @{input}.checked = @{value}; );
@@ -0,0 +1,225 @@
+//! This module contains the implementation of a virtual widget `VWidget`
+//! which embeds an external (non-yew) widget like an editor, a map or a chart.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::marker::PhantomData;
+use stdweb::web::{document, Element, INode, Node};
+use html::{Component, Scope};
+use super::{Reform, VDiff, VNode};
+
+/// An interface of an external widget which is controlled by a third-party
+/// library and lives inside of a stable host element.
+pub trait Widget: Sized + 'static {
+    /// Configuration of the widget. The widget is updated only when
+    /// a new configuration is not equal to the previous one.
+    type Config: PartialEq + 'static;
+
+    /// Tag of the host element which will be created for the widget.
+    fn host_tag() -> &'static str {
+        "div"
+    }
+
+    /// Called once when the host element was mounted to the DOM.
+    fn init(host: &Element, config: &Self::Config) -> Self;
+
+    /// Called every time when the configuration had changed.
+    fn update(&mut self, host: &Element, config: &Self::Config);
+
+    /// Called before the host element will be removed from the DOM.
+    fn destroy(&mut self, _host: &Element) {}
+}
+
+/// A type-erased instance of the mounted widget.
+trait Mounted {
+    /// Updates the widget if the configuration changed.
+    fn update(&mut self, host: &Element, config: Box<dyn Any>);
+    /// Destroys the widget.
+    fn destroy(&mut self, host: &Element);
+    /// Returns the current configuration of the widget.
+    fn config(&self) -> &dyn Any;
+}
+
+struct MountedWidget<W: Widget> {
+    widget: W,
+    config: W::Config,
+}
+
+impl<W: Widget> Mounted for MountedWidget<W> {
+    fn update(&mut self, host: &Element, config: Box<dyn Any>) {
+        let config = *config
+            .downcast::<W::Config>()
+            .expect("tried to update a widget with a config of the other widget");
+        if self.config != config {
+            self.config = config;
+            self.widget.update(host, &self.config);
+        }
+    }
+
+    fn destroy(&mut self, host: &Element) {
+        self.widget.destroy(host);
+    }
+
+    fn config(&self) -> &dyn Any {
+        &self.config
+    }
+}
+
+/// The method creates an instance of a widget inside of the host element.
+type Initializer = fn(&Element, Box<dyn Any>) -> Box<dyn Mounted>;
+
+fn initialize<W: Widget>(host: &Element, config: Box<dyn Any>) -> Box<dyn Mounted> {
+    let config = *config
+        .downcast::<W::Config>()
+        .expect("tried to init a widget with a config of the other widget");
+    let widget = W::init(host, &config);
+    Box::new(MountedWidget { widget, config })
+}
+
+/// The method compares configurations of the same widget.
+type Comparator = fn(&dyn Any, &dyn Any) -> bool;
+
+fn compare<W: Widget>(left: &dyn Any, right: &dyn Any) -> bool {
+    match (left.downcast_ref::<W::Config>(), right.downcast_ref::<W::Config>()) {
+        (Some(left), Some(right)) => left == right,
+        _ => false,
+    }
+}
+
+/// A virtual node which holds an external widget.
+pub struct VWidget<COMP: Component> {
+    type_id: TypeId,
+    tag: &'static str,
+    config: Option<Box<dyn Any>>,
+    initializer: Initializer,
+    comparator: Comparator,
+    mounted: Option<Box<dyn Mounted>>,
+    /// A reference to the host `Element`.
+    pub reference: Option<Element>,
+    _comp: PhantomData<COMP>,
+}
+
+impl<COMP: Component> VWidget<COMP> {
+    /// Creates a new virtual widget with a configuration.
+    pub fn new<W: Widget>(config: W::Config) -> Self {
+        VWidget {
+            type_id: TypeId::of::<W>(),
+            tag: W::host_tag(),
+            config: Some(Box::new(config)),
+            initializer: initialize::<W>,
+            comparator: compare::<W>,
+            mounted: None,
+            reference: None,
+            _comp: PhantomData,
+        }
+    }
+
+    /// Returns the configuration which is not applied yet or
+    /// the configuration of the mounted widget.
+    fn current_config(&self) -> Option<&dyn Any> {
+        self.config
+            .as_ref()
+            .map(|config| config.as_ref())
+            .or_else(|| self.mounted.as_ref().map(|mounted| mounted.config()))
+    }
+}
+
+impl<COMP: Component> VDiff for VWidget<COMP> {
+    type Component = COMP;
+
+    /// Destroys the widget and removes the host element from parent.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let host = self.reference.take()
+            .expect("tried to remove not rendered VWidget from DOM");
+        if let Some(mut mounted) = self.mounted.take() {
+            mounted.destroy(&host);
+        }
+        let sibling = host.next_sibling();
+        if parent.remove_child(&host).is_err() {
+            warn!("Node not found to remove VWidget");
+        }
+        sibling
+    }
+
+    /// Reuses the host element and the instance of the same widget
+    /// or initializes a new one.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let reform = {
+            match ancestor {
+                Some(VNode::VWidget(mut vwidget)) => {
+                    if self.type_id == vwidget.type_id {
+                        self.reference = vwidget.reference.take();
+                        self.mounted = vwidget.mounted.take();
+                        Reform::Keep
+                    } else {
+                        let node = vwidget.detach(parent);
+                        Reform::Before(node)
+                    }
+                }
+                Some(mut vnode) => {
+                    let node = vnode.detach(parent);
+                    Reform::Before(node)
+                }
+                None => Reform::Before(None),
+            }
+        };
+        let config = self.config.take().expect("tried to apply a widget twice");
+        match reform {
+            Reform::Keep => {
+                let host = self.reference.as_ref().expect("host element expected");
+                self.mounted.as_mut()
+                    .expect("widget was not initialized")
+                    .update(host, config);
+            }
+            Reform::Before(before) => {
+                let host = document()
+                    .create_element(self.tag)
+                    .expect("can't create host element for a widget");
+                if let Some(sibling) = before {
+                    parent
+                        .insert_before(&host, &sibling)
+                        .expect("can't insert widget before sibling");
+                } else {
+                    let precursor = precursor.and_then(|before| before.next_sibling());
+                    if let Some(precursor) = precursor {
+                        parent
+                            .insert_before(&host, &precursor)
+                            .expect("can't insert widget before precursor");
+                    } else {
+                        parent.append_child(&host);
+                    }
+                }
+                // Initialize the widget when the host is in the DOM already
+                self.mounted = Some((self.initializer)(&host, config));
+                self.reference = Some(host);
+            }
+        }
+        self.reference.as_ref().map(|host| host.as_node().to_owned())
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VWidget<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VWidget {{ tag: {} }}", self.tag)
+    }
+}
+
+impl<COMP: Component> PartialEq for VWidget<COMP> {
+    fn eq(&self, other: &VWidget<COMP>) -> bool {
+        if self.type_id != other.type_id {
+            return false;
+        }
+        match (self.current_config(), other.current_config()) {
+            (Some(left), Some(right)) => (self.comparator)(left, right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
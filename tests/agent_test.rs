@@ -0,0 +1,223 @@
+extern crate serde_derive;
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use serde_derive::{Serialize, Deserialize};
+use yew::agent::{Agent, AgentHarness, AgentLink, Bridge, Bridged, Context, HandlerId, Job};
+use yew::agent::{RequestError, RequestId, Transferable};
+use yew::services::Task;
+use yew::callback::Callback;
+
+struct Counter {
+    link: AgentLink<Counter>,
+    value: u32,
+    pending: Option<RequestId>,
+}
+
+enum Msg {
+    Reset,
+    Answer,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Input {
+    Add(u32),
+    Get,
+    GetLater,
+}
+
+impl Transferable for Input {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Output {
+    Value(u32),
+    Connected(usize),
+}
+
+impl Transferable for Output {}
+
+impl Agent for Counter {
+    type Reach = Job;
+    type Message = Msg;
+    type Input = Input;
+    type Output = Output;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Counter {
+            link,
+            value: 0,
+            pending: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Reset => {
+                self.value = 0;
+                self.link.broadcast(Output::Value(self.value));
+            }
+            Msg::Answer => {
+                if let Some(request) = self.pending.take() {
+                    self.link.respond(request, Output::Value(self.value));
+                }
+            }
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        let count = self.link.connected().len();
+        self.link.response(id, Output::Connected(count));
+    }
+
+    fn handle(&mut self, msg: Self::Input, id: HandlerId) {
+        match msg {
+            Input::Add(value) => {
+                self.value += value;
+                self.link.broadcast(Output::Value(self.value));
+            }
+            Input::Get => {
+                self.link.response(id, Output::Value(self.value));
+            }
+            Input::GetLater => {}
+        }
+    }
+
+    fn handle_request(&mut self, msg: Self::Input, request: RequestId) {
+        match msg {
+            Input::GetLater => {
+                self.pending = Some(request);
+            }
+            msg => {
+                self.handle(msg, request.handler());
+            }
+        }
+    }
+}
+
+#[test]
+fn collects_outputs_of_handlers() {
+    let mut harness = AgentHarness::<Counter>::new();
+    let first = harness.connect();
+    let second = harness.connect();
+    assert_eq!(harness.take_outputs(first), vec![Output::Connected(1)]);
+    assert_eq!(harness.take_outputs(second), vec![Output::Connected(2)]);
+
+    harness.send(first, Input::Add(2));
+    harness.send(second, Input::Get);
+    assert_eq!(harness.take_outputs(first), vec![Output::Value(2)]);
+    assert_eq!(harness.take_outputs(second), vec![Output::Value(2), Output::Value(2)]);
+    assert!(harness.take_outputs(first).is_empty());
+}
+
+#[test]
+fn does_not_broadcast_to_dispatchers_and_disconnected_handlers() {
+    let mut harness = AgentHarness::<Counter>::new();
+    let bridge = harness.connect();
+    let dispatcher = harness.connect_dispatcher();
+    let gone = harness.connect();
+    assert!(!dispatcher.is_respondable());
+    harness.disconnect(gone);
+    harness.take_outputs(bridge);
+    harness.take_outputs(gone);
+
+    harness.send(dispatcher, Input::Add(5));
+    harness.send_message(Msg::Reset);
+    assert_eq!(harness.take_outputs(bridge), vec![Output::Value(5), Output::Value(0)]);
+    assert!(harness.take_outputs(dispatcher).is_empty());
+    assert!(harness.take_outputs(gone).is_empty());
+}
+
+#[test]
+fn answers_requests() {
+    let mut harness = AgentHarness::<Counter>::new();
+    let bridge = harness.connect();
+    harness.take_outputs(bridge);
+    harness.send(bridge, Input::Add(3));
+    harness.take_outputs(bridge);
+
+    let request = harness.request(bridge, Input::Get);
+    assert_eq!(harness.take_response(request), Some(Output::Value(3)));
+    assert!(harness.take_outputs(bridge).is_empty());
+
+    let request = harness.request(bridge, Input::GetLater);
+    assert_eq!(harness.take_response(request), None);
+    harness.send_message(Msg::Answer);
+    assert_eq!(harness.take_response(request), Some(Output::Value(3)));
+}
+
+#[test]
+fn answers_requests_by_broadcast() {
+    let mut harness = AgentHarness::<Counter>::new();
+    let bridge = harness.connect();
+    let other = harness.connect();
+    harness.take_outputs(bridge);
+    harness.take_outputs(other);
+
+    let request = harness.request(bridge, Input::Add(4));
+    assert_eq!(harness.take_response(request), Some(Output::Value(4)));
+    assert!(harness.take_outputs(bridge).is_empty());
+    assert_eq!(harness.take_outputs(other), vec![Output::Value(4)]);
+}
+
+struct Echo {
+    link: AgentLink<Echo>,
+}
+
+impl Agent for Echo {
+    type Reach = Context;
+    type Message = ();
+    type Input = Input;
+    type Output = Output;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Echo { link }
+    }
+
+    fn update(&mut self, _: Self::Message) {}
+
+    fn handle(&mut self, msg: Self::Input, _: HandlerId) {
+        if let Input::Add(value) = msg {
+            self.link.broadcast(Output::Value(value));
+        }
+    }
+}
+
+#[test]
+fn drops_bridge_inside_of_its_callback() {
+    let bridge: Rc<RefCell<Option<Box<dyn Bridge<Echo>>>>> = Rc::new(RefCell::new(None));
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let callback = {
+        let bridge = bridge.clone();
+        let received = received.clone();
+        Callback::from(move |output| {
+            received.borrow_mut().push(output);
+            // Drops the last bridge like a component which is destroyed by the output
+            bridge.borrow_mut().take();
+        })
+    };
+    *bridge.borrow_mut() = Some(Echo::bridge(callback));
+    let mut other = Echo::bridge(Callback::from(|_| {}));
+    other.send(Input::Add(1));
+    other.send(Input::Add(2));
+    assert!(bridge.borrow().is_none());
+    assert_eq!(*received.borrow(), vec![Output::Value(1)]);
+}
+
+struct SendOnly;
+
+impl Bridge<Echo> for SendOnly {
+    fn send(&mut self, _: Input) {}
+}
+
+#[test]
+fn fails_requests_of_custom_bridges() {
+    let result = Rc::new(RefCell::new(None));
+    let callback = {
+        let result = result.clone();
+        Callback::from(move |response| *result.borrow_mut() = Some(response))
+    };
+    let task = SendOnly.request(Input::Get, callback, None);
+    assert!(!task.is_active());
+    assert_eq!(*result.borrow(), Some(Err(RequestError::Unsupported)));
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "web_test")]
+
+extern crate stdweb;
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use stdweb::web::{document, INode};
+use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+struct Notifier {
+    link: ComponentLink<Notifier>,
+    destroyed: Rc<Cell<bool>>,
+}
+
+impl Component for Notifier {
+    type Message = ();
+    type Properties = Rc<Cell<bool>>;
+
+    fn create(destroyed: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Notifier { link, destroyed }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        true
+    }
+
+    fn destroy(&mut self) {
+        // The message is processed after the destroying and ignored
+        self.link.send_self(());
+        self.destroyed.set(true);
+    }
+}
+
+impl Renderable<Notifier> for Notifier {
+    fn view(&self) -> Html<Self> {
+        html! { <p>{ "Notifier" }</p> }
+    }
+}
+
+#[test]
+fn unmounts_component_which_sends_messages_in_destroy() {
+    yew::initialize();
+    let element = document().create_element("div").unwrap();
+    let destroyed = Rc::new(Cell::new(false));
+    let handle = App::<Notifier>::new().mount_with_props(element.clone(), destroyed.clone());
+    handle.unmount();
+    assert!(destroyed.get());
+    assert!(element.first_child().is_none());
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "web_test")]
+
+extern crate stdweb;
+extern crate yew;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use stdweb::web::{document, IElement, INode, IParentNode};
+use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::custom_element::{self, CustomElement};
+
+static DESTROYED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, PartialEq, Default)]
+struct Props {
+    label: String,
+}
+
+struct Badge {
+    link: ComponentLink<Badge>,
+    props: Props,
+}
+
+impl Component for Badge {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Badge { link, props }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn destroy(&mut self) {
+        self.link.send_self(());
+        DESTROYED.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Renderable<Badge> for Badge {
+    fn view(&self) -> Html<Self> {
+        html! { <span>{ &self.props.label }</span> }
+    }
+}
+
+impl CustomElement for Badge {
+    fn observed_attributes() -> &'static [&'static str] {
+        &["label"]
+    }
+
+    fn set_attribute(props: &mut Self::Properties, _: &str, value: Option<String>) {
+        props.label = value.unwrap_or_default();
+    }
+}
+
+#[test]
+fn unmounts_component_when_element_is_removed() {
+    yew::initialize();
+    custom_element::define::<Badge>("test-badge");
+    let body = document().query_selector("body").unwrap().unwrap();
+    let element = document().create_element("test-badge").unwrap();
+    element.append_child(&document().create_text_node("Light DOM"));
+    element.set_attribute("label", "New").unwrap();
+    body.append_child(&element);
+    assert_eq!(element.child_nodes().len(), 2);
+
+    body.remove_child(&element).unwrap();
+    assert!(DESTROYED.load(Ordering::SeqCst));
+    assert_eq!(element.child_nodes().len(), 1);
+}
@@ -0,0 +1,131 @@
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::hooks::{HookHarness, Reducer};
+use yew::html::ShouldRender;
+
+struct Counter {
+    value: i32,
+}
+
+enum Action {
+    Add(i32),
+    Keep,
+}
+
+impl Reducer for Counter {
+    type Action = Action;
+
+    fn reduce(&mut self, action: Self::Action) -> ShouldRender {
+        match action {
+            Action::Add(delta) => {
+                self.value += delta;
+                true
+            }
+            Action::Keep => false,
+        }
+    }
+}
+
+#[test]
+fn state_schedules_renders() {
+    let mut harness = HookHarness::new();
+    let state = harness.use_state(1);
+    harness.render();
+
+    state.set(2);
+    state.update(|value| *value *= 10);
+    assert_eq!(*state.get(), 20);
+    assert_eq!(harness.renders(), 2);
+
+    state.setter().emit(5);
+    assert_eq!(*state.get(), 5);
+    assert_eq!(harness.renders(), 3);
+}
+
+#[test]
+fn reducer_renders_only_when_necessary() {
+    let mut harness = HookHarness::new();
+    let counter = harness.use_reducer(Counter { value: 0 });
+    harness.render();
+
+    counter.dispatch(Action::Add(3));
+    counter.dispatch(Action::Keep);
+    counter.dispatcher().emit(Action::Add(-1));
+    assert_eq!(counter.get().value, 2);
+    assert_eq!(harness.renders(), 2);
+}
+
+#[test]
+fn effects_run_in_order_and_clean_up() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut harness = HookHarness::new();
+    let deps = harness.use_state(0);
+    for name in &["first", "second"] {
+        let deps = deps.clone();
+        let log = log.clone();
+        harness.use_effect(
+            move || *deps.get(),
+            move |value| {
+                log.borrow_mut().push(format!("{} {}", name, value));
+                let log = log.clone();
+                let value = *value;
+                Some(Box::new(move || {
+                    log.borrow_mut().push(format!("cleanup {} {}", name, value));
+                }))
+            },
+        );
+    }
+
+    harness.render();
+    harness.render();
+    assert_eq!(*log.borrow(), vec!["first 0", "second 0"]);
+
+    deps.set(1);
+    harness.render();
+    assert_eq!(
+        *log.borrow(),
+        vec!["first 0", "second 0", "cleanup first 0", "first 1", "cleanup second 0", "second 1"]
+    );
+
+    log.borrow_mut().clear();
+    drop(harness);
+    assert_eq!(*log.borrow(), vec!["cleanup first 1", "cleanup second 1"]);
+}
+
+#[test]
+fn memo_recomputes_when_deps_change() {
+    let computed = Rc::new(RefCell::new(0));
+    let mut harness = HookHarness::new();
+    let source = harness.use_state(2);
+    let square = {
+        let source = source.clone();
+        let computed = computed.clone();
+        harness.use_memo(
+            move || *source.get(),
+            move |value| {
+                *computed.borrow_mut() += 1;
+                value * value
+            },
+        )
+    };
+    harness.render();
+
+    assert_eq!(*square.get(), 4);
+    assert_eq!(*square.get(), 4);
+    assert_eq!(*computed.borrow(), 1);
+
+    source.set(3);
+    assert_eq!(*square.get(), 9);
+    assert_eq!(*computed.borrow(), 2);
+}
+
+#[test]
+#[should_panic(expected = "hooks have to be registered in `Component::create` only")]
+fn hooks_are_not_registered_after_create() {
+    let mut harness = HookHarness::new();
+    harness.use_state(0);
+    harness.render();
+    harness.use_state(0);
+}
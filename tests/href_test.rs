@@ -0,0 +1,134 @@
+extern crate yew;
+
+use yew::{html, Component, ComponentLink, Href, Html, Renderable, ShouldRender};
+use yew::virtual_dom::VNode;
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
+#[test]
+fn it_keeps_safe_urls() {
+    assert_eq!(Href::from("https://example.com/").to_string(), "https://example.com/");
+    assert_eq!(Href::from("  mailto:me@example.com ").to_string(), "mailto:me@example.com");
+    assert_eq!(Href::from("#/active").to_string(), "#/active");
+    assert_eq!(Href::from("/path:with/colon").to_string(), "/path:with/colon");
+    assert_eq!(Href::from("../relative").to_string(), "../relative");
+}
+
+#[test]
+fn it_blocks_script_urls() {
+    assert_eq!(Href::from("javascript:alert(1)").to_string(), "about:invalid");
+    assert_eq!(Href::from(" \u{1}JavaScript:alert(1)").to_string(), "about:invalid");
+    assert_eq!(Href::from("java\tscript:alert(1)").to_string(), "about:invalid");
+    assert_eq!(Href::from("data:text/html,<script>").to_string(), "about:invalid");
+}
+
+#[test]
+fn it_uses_allowed_schemes() {
+    Href::set_allowed_schemes(&["https", "DATA"]);
+    assert!(Href::is_allowed("data:image/png;base64,AAAA"));
+    assert!(!Href::is_allowed("http://example.com/"));
+    assert!(Href::is_allowed("relative/path"));
+}
+
+#[test]
+fn it_trusts_explicitly() {
+    assert_eq!(Href::trusted("javascript:void(0)").to_string(), "javascript:void(0)");
+}
+
+#[test]
+fn it_checks_url_attributes() {
+    let a: VNode<Comp> = html! {
+        <form action="javascript:alert(1)",>
+            <img src="javascript:alert(2)",/>
+            <button formaction="https://example.com/",></button>
+        </form>
+    };
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.attributes.get("action"), Some(&"about:invalid".into()));
+        if let VNode::VTag(ref img) = vtag.childs[0] {
+            assert_eq!(img.attributes.get("src"), Some(&"about:invalid".into()));
+        } else {
+            panic!("vtag expected");
+        }
+        if let VNode::VTag(ref button) = vtag.childs[1] {
+            assert_eq!(
+                button.attributes.get("formaction"),
+                Some(&"https://example.com/".into())
+            );
+        } else {
+            panic!("vtag expected");
+        }
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_accepts_string_references() {
+    let url = "https://example.com/logo.png".to_string();
+    let a: VNode<Comp> = html! {
+        <img src=&url,/>
+    };
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.attributes.get("src"), Some(&"https://example.com/logo.png".into()));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_allows_inline_and_local_sources() {
+    let a: VNode<Comp> = html! {
+        <div>
+            <img src="data:image/png;base64,AAAA",/>
+            <video src="blob:https://example.com/5c1e",></video>
+            <a href="data:text/html,<script>",></a>
+        </div>
+    };
+    if let VNode::VTag(vtag) = a {
+        if let VNode::VTag(ref img) = vtag.childs[0] {
+            assert_eq!(img.attributes.get("src"), Some(&"data:image/png;base64,AAAA".into()));
+        } else {
+            panic!("vtag expected");
+        }
+        if let VNode::VTag(ref video) = vtag.childs[1] {
+            assert_eq!(
+                video.attributes.get("src"),
+                Some(&"blob:https://example.com/5c1e".into())
+            );
+        } else {
+            panic!("vtag expected");
+        }
+        if let VNode::VTag(ref link) = vtag.childs[2] {
+            assert_eq!(link.attributes.get("href"), Some(&"about:invalid".into()));
+        } else {
+            panic!("vtag expected");
+        }
+    } else {
+        panic!("vtag expected");
+    }
+
+    assert_eq!(Href::from("javascript:alert(1)").to_source_string(), "about:invalid");
+    Href::set_allowed_source_schemes(&["https"]);
+    assert!(!Href::is_allowed_source("data:image/png;base64,AAAA"));
+    assert!(Href::is_allowed_source("images/logo.png"));
+}
@@ -0,0 +1,78 @@
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use yew::services::{Task, TaskRegistry};
+
+struct DummyTask {
+    active: bool,
+    canceled: Rc<Cell<usize>>,
+}
+
+fn dummy_task(canceled: &Rc<Cell<usize>>) -> Box<dyn Task> {
+    Box::new(DummyTask {
+        active: true,
+        canceled: canceled.clone(),
+    })
+}
+
+impl Task for DummyTask {
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn cancel(&mut self) {
+        self.active = false;
+        self.canceled.set(self.canceled.get() + 1);
+    }
+}
+
+impl Drop for DummyTask {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+#[test]
+fn replaces_task_with_the_same_key() {
+    let first = Rc::new(Cell::new(0));
+    let second = Rc::new(Cell::new(0));
+    let mut registry = TaskRegistry::default();
+    registry.insert("fetch".into(), dummy_task(&first));
+    assert!(registry.is_active("fetch"));
+
+    registry.insert("fetch".into(), dummy_task(&second));
+    assert_eq!(first.get(), 1);
+    assert_eq!(second.get(), 0);
+    assert!(registry.is_active("fetch"));
+}
+
+#[test]
+fn cancels_task_by_key() {
+    let canceled = Rc::new(Cell::new(0));
+    let mut registry = TaskRegistry::default();
+    registry.insert("timeout".into(), dummy_task(&canceled));
+
+    assert!(!registry.cancel("interval"));
+    assert!(registry.cancel("timeout"));
+    assert_eq!(canceled.get(), 1);
+    assert!(!registry.is_active("timeout"));
+    assert!(!registry.cancel("timeout"));
+}
+
+#[test]
+fn cancels_all_tasks_on_destroy() {
+    let canceled = Rc::new(Cell::new(0));
+    let mut registry = TaskRegistry::default();
+    registry.insert("fetch".into(), dummy_task(&canceled));
+    registry.insert("timeout".into(), dummy_task(&canceled));
+    registry.push(dummy_task(&canceled));
+    registry.push(dummy_task(&canceled));
+
+    registry.cancel_all();
+    assert_eq!(canceled.get(), 4);
+    assert!(!registry.is_active("fetch"));
+    assert!(!registry.is_active("timeout"));
+}
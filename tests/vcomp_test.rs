@@ -1,6 +1,6 @@
 extern crate yew;
 
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{function_component, html, Component, ComponentLink, Html, Renderable, ShouldRender};
 use yew::virtual_dom::VNode;
 
 struct Comp;
@@ -66,3 +66,50 @@ fn set_properties_to_component() {
         <Comp: with props, field_2=2, />
     };
 }
+
+#[derive(PartialEq, Clone, Default)]
+struct GreetingProps {
+    name: String,
+}
+
+function_component! {
+    /// A component which is rendered by a function.
+    fn Greeting(props: &GreetingProps) -> Html {
+        html! {
+            <p>{ format!("Hello, {}!", props.name) }</p>
+        }
+    }
+}
+
+function_component! {
+    fn Separator() -> Html {
+        html! { <hr/> }
+    }
+}
+
+#[test]
+fn set_properties_to_function_component() {
+    let _: VNode<Comp> = html! {
+        <div>
+            <Greeting: name="World", />
+            <Separator: />
+        </div>
+    };
+}
+
+#[test]
+fn function_component_changes_only_with_other_props() {
+    let props = GreetingProps {
+        name: "World".into(),
+    };
+    let mut greeting = Greeting {
+        props: props.clone(),
+    };
+    assert!(!greeting.change(props));
+
+    let other = GreetingProps {
+        name: "Yew".into(),
+    };
+    assert!(greeting.change(other.clone()));
+    assert!(!greeting.change(other));
+}
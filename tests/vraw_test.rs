@@ -0,0 +1,98 @@
+extern crate yew;
+
+use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::virtual_dom::VNode;
+use yew::virtual_dom::vraw::{sanitize, VRaw};
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
+#[test]
+fn it_compares_raw_html() {
+    let a: VNode<Comp> = html! {
+        <div>{ VRaw::new("<b>bold</b>".into()) }</div>
+    };
+
+    let b: VNode<Comp> = html! {
+        <div>{ VRaw::new("<b>bold</b>".into()) }</div>
+    };
+
+    let c: VNode<Comp> = html! {
+        <div>{ VRaw::sanitized("<b>bold</b>".into()) }</div>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn sanitizer_removes_scripts() {
+    assert_eq!(
+        sanitize("<p>text<script>alert(1)</script> end</p>"),
+        "<p>text end</p>"
+    );
+    assert_eq!(sanitize("<SCRIPT src=\"x.js\"></SCRIPT>ok"), "ok");
+    assert_eq!(sanitize("<iframe src=\"x\"><b>in</b></iframe>ok"), "ok");
+}
+
+#[test]
+fn sanitizer_removes_event_handlers() {
+    assert_eq!(
+        sanitize("<img src=\"a.png\" onerror=\"alert(1)\" alt='pic'>"),
+        "<img src=\"a.png\" alt=\"pic\">"
+    );
+    assert_eq!(sanitize("<div OnClick=alert(1)>x</div>"), "<div>x</div>");
+}
+
+#[test]
+fn sanitizer_removes_script_urls() {
+    assert_eq!(sanitize("<a href=\"javascript:alert(1)\">x</a>"), "<a>x</a>");
+    assert_eq!(sanitize("<a href=\" JaVa\tScRiPt:alert(1)\">x</a>"), "<a>x</a>");
+    assert_eq!(sanitize("<a href=\"jav&#x61;script:alert(1)\">x</a>"), "<a>x</a>");
+    assert_eq!(
+        sanitize("<a href=\"https://example.com/?a=1&amp;b=2\">x</a>"),
+        "<a href=\"https://example.com/?a=1&amp;b=2\">x</a>"
+    );
+    assert_eq!(sanitize("<a href=\"/path:with/colon\">x</a>"), "<a href=\"/path:with/colon\">x</a>");
+}
+
+#[test]
+fn sanitizer_removes_svg_animations() {
+    assert_eq!(
+        sanitize("<svg><set attributeName=href to=javascript:alert(1) /></svg>"),
+        "<svg></svg>"
+    );
+    assert_eq!(
+        sanitize("<svg><a><animate attributeName=href values=javascript:alert(1)></animate>x</a></svg>"),
+        "<svg><a>x</a></svg>"
+    );
+    assert_eq!(
+        sanitize("<svg><animateMotion dur=\"1s\"><mpath /></animateMotion></svg>"),
+        "<svg></svg>"
+    );
+}
+
+#[test]
+fn sanitizer_keeps_text() {
+    assert_eq!(sanitize("1 < 2 and 3 > 2"), "1 &lt; 2 and 3 > 2");
+    assert_eq!(sanitize("a<!-- <script>alert(1)</script> -->b"), "ab");
+    assert_eq!(sanitize("<br/>"), "<br />");
+}
@@ -1,7 +1,7 @@
 extern crate yew;
 
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
-use yew::virtual_dom::VNode;
+use yew::{classes, html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::virtual_dom::{Attributes, VNode};
 
 struct Comp;
 
@@ -254,3 +254,184 @@ fn it_allows_aria_attributes() {
         panic!("vtag expected");
     }
 }
+
+#[test]
+fn supports_conditional_classes() {
+    let active = true;
+    let disabled = false;
+    let extra = Some("class-3".to_string());
+    let a: VNode<Comp> = html! {
+        <div class=classes!("class-1", ("class-2", active), ("class-4", disabled), extra),></div>
+    };
+
+    let b: VNode<Comp> = html! {
+        <div class="class-3 class-2 class-1",></div>
+    };
+
+    assert_eq!(a, b);
+
+    if let VNode::VTag(vtag) = a {
+        let classes = vtag.classes.iter().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(classes, vec!["class-1", "class-2", "class-3"]);
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn supports_classes_from_collections() {
+    let list = vec!["class-1", "class-2"];
+    let a: VNode<Comp> = html! {
+        <div class=list,></div>
+    };
+
+    let none: Option<&str> = None;
+    let b: VNode<Comp> = html! {
+        <div class=("class-1", none, ("class-2 class-1", true)),></div>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn supports_dynamic_tag_names() {
+    let level = 2;
+    let tag = format!("h{}", level);
+    let a: VNode<Comp> = html! {
+        <@{tag} class="title",>{ "Heading" }</@>
+    };
+
+    let b: VNode<Comp> = html! {
+        <h2 class="title",>{ "Heading" }</h2>
+    };
+
+    assert_eq!(a, b);
+
+    let link = false;
+    let c: VNode<Comp> = html! {
+        <@{ if link { "a" } else { "button" } }/>
+    };
+
+    if let VNode::VTag(vtag) = c {
+        assert_eq!(vtag.tag(), "button");
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn supports_attributes_spreading() {
+    let mut attrs = Attributes::new();
+    attrs.insert("id".into(), "main".into());
+    attrs.insert("class".into(), "class-2".into());
+    attrs.insert("href".into(), "javascript:alert(1)".into());
+    let a: VNode<Comp> = html! {
+        <a class="class-1", ..attrs, role="button",></a>
+    };
+
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.attributes.get("id"), Some(&"main".into()));
+        assert_eq!(vtag.attributes.get("role"), Some(&"button".into()));
+        assert_eq!(vtag.attributes.get("href"), Some(&"about:invalid".into()));
+        assert!(vtag.classes.contains("class-1"));
+        assert!(vtag.classes.contains("class-2"));
+    } else {
+        panic!("vtag expected");
+    }
+
+    let b: VNode<Comp> = html! {
+        <div ..vec![("data-value", 5)],></div>
+    };
+
+    if let VNode::VTag(vtag) = b {
+        assert_eq!(vtag.attributes.get("data-value"), Some(&"5".into()));
+    } else {
+        panic!("vtag expected");
+    }
+
+    let c: VNode<Comp> = html! {
+        <input ..vec![("type", "checkbox"), ("value", "on"), ("checked", "true"), ("src", "javascript:")],/>
+    };
+
+    if let VNode::VTag(vtag) = c {
+        assert_eq!(vtag.kind, Some("checkbox".into()));
+        assert_eq!(vtag.value, Some("on".into()));
+        assert!(vtag.checked);
+        assert_eq!(vtag.attributes.get("src"), Some(&"about:invalid".into()));
+        assert!(!vtag.attributes.contains_key("type"));
+        assert!(!vtag.attributes.contains_key("value"));
+        assert!(!vtag.attributes.contains_key("checked"));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn supports_inline_conditions() {
+    let render = |count: u32| -> VNode<Comp> {
+        html! {
+            <div>
+                { @if count == 0 {
+                    <span class="empty",>{ "Nothing" }</span>
+                } else if count == 1 {
+                    <b>{ "One" }</b>
+                } else {
+                    <i>{ count }</i>
+                } }
+                { @if count > 5 { <p>{ "Many" }</p> } }
+            </div>
+        }
+    };
+
+    assert_eq!(
+        render(0),
+        html! { <div><span class="empty",>{ "Nothing" }</span>{ "" }</div> }
+    );
+    assert_eq!(render(1), html! { <div><b>{ "One" }</b>{ "" }</div> });
+    assert_eq!(render(7), html! { <div><i>{ 7 }</i><p>{ "Many" }</p></div> });
+}
+
+#[test]
+fn supports_inline_match() {
+    let render = |value: Option<u32>| -> VNode<Comp> {
+        html! {
+            <div>
+                { @match value {
+                    Some(0) | None => { <span>{ "Nothing" }</span> }
+                    Some(x) if x > 10 => { <b>{ "Big" }</b> },
+                    Some(x) => { <i>{ x }</i> },
+                } }
+            </div>
+        }
+    };
+
+    assert_eq!(render(None), html! { <div><span>{ "Nothing" }</span></div> });
+    assert_eq!(render(Some(0)), html! { <div><span>{ "Nothing" }</span></div> });
+    assert_eq!(render(Some(11)), html! { <div><b>{ "Big" }</b></div> });
+    assert_eq!(render(Some(3)), html! { <div><i>{ 3 }</i></div> });
+
+    let root: VNode<Comp> = html! {
+        { @match value_of(2) { 2 => { <p></p> } _ => {} } }
+    };
+    assert_eq!(root, html! { <p></p> });
+}
+
+fn value_of(value: u32) -> u32 {
+    value
+}
+
+#[test]
+fn keeps_if_and_match_expressions() {
+    let item = || -> Html<Comp> { html! { <span></span> } };
+    let render = |show: bool| -> VNode<Comp> {
+        html! {
+            <div>
+                { if show { item() } else { html! { <p></p> } } }
+                { match show { true => "yes", false => "no" } }
+            </div>
+        }
+    };
+
+    assert_eq!(render(true), html! { <div><span></span>{ "yes" }</div> });
+    assert_eq!(render(false), html! { <div><p></p>{ "no" }</div> });
+}
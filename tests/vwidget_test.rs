@@ -0,0 +1,80 @@
+extern crate stdweb;
+extern crate yew;
+
+use stdweb::web::Element;
+use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::virtual_dom::{VNode, VWidget, Widget};
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
+struct Editor;
+
+impl Widget for Editor {
+    type Config = String;
+
+    fn init(_: &Element, _: &Self::Config) -> Self {
+        Editor
+    }
+
+    fn update(&mut self, _: &Element, _: &Self::Config) {
+    }
+}
+
+struct Chart;
+
+impl Widget for Chart {
+    type Config = ();
+
+    fn host_tag() -> &'static str {
+        "canvas"
+    }
+
+    fn init(_: &Element, _: &Self::Config) -> Self {
+        Chart
+    }
+
+    fn update(&mut self, _: &Element, _: &Self::Config) {
+    }
+}
+
+#[test]
+fn it_compares_widgets() {
+    let a: VNode<Comp> = html! {
+        <div>{ VWidget::new::<Editor>("text".into()) }</div>
+    };
+
+    let b: VNode<Comp> = html! {
+        <div>{ VWidget::new::<Editor>("other text".into()) }</div>
+    };
+
+    let c: VNode<Comp> = html! {
+        <div>{ VWidget::new::<Editor>("text".into()) }</div>
+    };
+
+    let d: VNode<Comp> = html! {
+        <div>{ VWidget::new::<Chart>(()) }</div>
+    };
+
+    assert_ne!(a, b);
+    assert_eq!(a, c);
+    assert_ne!(a, d);
+}